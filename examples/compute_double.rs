@@ -0,0 +1,71 @@
+use std::iter::once;
+use std::sync::mpsc;
+
+use wgpu::util::{BufferInitDescriptor, DeviceExt};
+use wgpu::{
+    BufferDescriptor, BufferUsages, CommandEncoderDescriptor, ComputePassDescriptor, Maintain,
+    MapMode, ShaderStages,
+};
+
+use wgpu_noboiler::app::AppCreator;
+use wgpu_noboiler::bind_group::{BindGroupCreator, BindGroupLayoutCreator};
+use wgpu_noboiler::compute_pipeline::{dispatch, ComputePipelineCreator};
+
+const COUNT: u32 = 100;
+
+fn main() {
+    let (app_data, _) = AppCreator::headless((), 1, 1).frames(0).run_headless();
+    let device = &app_data.device;
+
+    let values: Vec<f32> = (0..COUNT).map(|value| value as f32).collect();
+
+    let storage_buffer = device.create_buffer_init(&BufferInitDescriptor {
+        label: Some("Storage Buffer"),
+        contents: bytemuck::cast_slice(&values),
+        usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+    });
+
+    let read_buffer = device.create_buffer(&BufferDescriptor {
+        label: Some("Read Buffer"),
+        size: storage_buffer.size(),
+        usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let bind_group_layout = BindGroupLayoutCreator::new(device)
+        .add_storage(ShaderStages::COMPUTE, false)
+        .build();
+
+    let bind_group = BindGroupCreator::new(device, &bind_group_layout)
+        .add_buffer(&storage_buffer)
+        .build();
+
+    let compute_pipeline = ComputePipelineCreator::from_shader_file("examples/shaderDouble.wgsl", device)
+        .add_bind_group(bind_group_layout.layout())
+        .build();
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor { label: None });
+
+    {
+        let mut compute_pass = encoder.begin_compute_pass(&ComputePassDescriptor { label: None });
+
+        compute_pass.set_pipeline(&compute_pipeline);
+        compute_pass.set_bind_group(0, &bind_group, &[]);
+
+        dispatch(&mut compute_pass, (COUNT, 1, 1), (64, 1, 1));
+    }
+
+    encoder.copy_buffer_to_buffer(&storage_buffer, 0, &read_buffer, 0, storage_buffer.size());
+    app_data.queue.submit(once(encoder.finish()));
+
+    let (sender, receiver) = mpsc::channel();
+    read_buffer
+        .slice(..)
+        .map_async(MapMode::Read, move |result| sender.send(result).unwrap());
+
+    device.poll(Maintain::Wait);
+    receiver.recv().unwrap().unwrap();
+
+    let doubled: Vec<f32> = bytemuck::cast_slice(&read_buffer.slice(..).get_mapped_range()).to_vec();
+    println!("{:?}", doubled);
+}
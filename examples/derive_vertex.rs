@@ -0,0 +1,98 @@
+use std::iter::once;
+
+use wgpu::{Color, CommandEncoder, TextureView};
+
+use wgpu_noboiler::app::{AppCreator, AppData};
+use wgpu_noboiler::buffer::{BufferCreator, SimpleBuffer};
+use wgpu_noboiler::pipeline_registry::PipelineRegistry;
+use wgpu_noboiler::render_pass::{RenderPassCreator, RenderPassExt};
+use wgpu_noboiler::render_pipeline::RenderPipelineCreator;
+use wgpu_noboiler::vertex::Vertex;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable, Vertex)]
+struct PosVertex {
+    position: [f32; 2],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable, Vertex)]
+#[vertex(instance)]
+struct Instance {
+    #[vertex(location = 1)]
+    offset: [f32; 2],
+    color: [f32; 3],
+}
+
+#[derive(Default)]
+struct State {
+    vertex_buffer: Option<SimpleBuffer>,
+    instance_buffer: Option<SimpleBuffer>,
+}
+
+fn main() {
+    AppCreator::new(State::default())
+        .init(init)
+        .render(render)
+        .title("Derive Vertex")
+        .run();
+}
+
+fn render(app_data: &AppData, state: &mut State, mut encoder: CommandEncoder, view: TextureView) {
+    {
+        let mut render_pass = RenderPassCreator::new(&view)
+            .clear_color(Color::BLACK)
+            .build(&mut encoder);
+
+        render_pass.set_pipeline(&app_data.render_pipelines["instanced"]);
+
+        render_pass.draw_instanced(
+            state.vertex_buffer.as_ref().unwrap(),
+            state.instance_buffer.as_ref().unwrap(),
+        );
+    }
+
+    app_data.queue.submit(once(encoder.finish()));
+}
+
+fn init(app_data: &AppData, state: &mut State, render_pipelines: &mut PipelineRegistry) {
+    state.vertex_buffer = Some(
+        BufferCreator::vertex(&app_data.device)
+            .data(vec![
+                PosVertex {
+                    position: [0.0, 0.4],
+                },
+                PosVertex {
+                    position: [-0.4, -0.4],
+                },
+                PosVertex {
+                    position: [0.4, -0.4],
+                },
+            ])
+            .build(),
+    );
+
+    state.instance_buffer = Some(
+        BufferCreator::instance(&app_data.device)
+            .data(vec![
+                Instance {
+                    offset: [-0.5, 0.0],
+                    color: [1.0, 0.0, 0.0],
+                },
+                Instance {
+                    offset: [0.5, 0.0],
+                    color: [0.0, 0.0, 1.0],
+                },
+            ])
+            .build(),
+    );
+
+    RenderPipelineCreator::from_shader_file(
+        "examples/shaderInstanced.wgsl",
+        &app_data.device,
+        app_data,
+    )
+    .add_vertex_buffer(PosVertex::descriptor())
+    .add_vertex_buffer(Instance::descriptor())
+    .build_watched("instanced", app_data, render_pipelines);
+}
@@ -1,10 +1,11 @@
 use std::iter::once;
 
-use wgpu::{Color, CommandEncoder, RenderPass, RenderPipeline, TextureView, VertexAttribute};
+use wgpu::{Color, CommandEncoder, RenderPass, TextureView, VertexAttribute};
 
 use wgpu_noboiler::app::{AppCreator, AppData};
-use wgpu_noboiler::buffer::BufferCreator;
-use wgpu_noboiler::render_pass::RenderPassCreator;
+use wgpu_noboiler::buffer::{BufferCreator, SimpleBuffer};
+use wgpu_noboiler::pipeline_registry::PipelineRegistry;
+use wgpu_noboiler::render_pass::{RenderPassCreator, RenderPassExt};
 use wgpu_noboiler::render_pipeline::RenderPipelineCreator;
 use wgpu_noboiler::vertex::Vertex;
 
@@ -19,65 +20,76 @@ impl Vertex<2> for ColoredVertex {
     const ATTRIBS: [VertexAttribute; 2] = wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3];
 }
 
+#[derive(Default)]
+struct State {
+    vertex_buffer: Option<SimpleBuffer>,
+    indices_buffer: Option<SimpleBuffer>,
+}
+
 fn main() {
-    AppCreator::new(())
+    AppCreator::new(State::default())
         .init(init)
         .render(render)
         .title("Rect")
         .resizable(false)
+        .watch_shaders(true)
         .run();
 }
 
-fn render(app_data: &AppData, _: &mut (), mut encoder: CommandEncoder, view: TextureView) {
-    let vertex_buffer = BufferCreator::vertex(&app_data.device)
-        .data(vec![
-            ColoredVertex {
-                position: [-0.5, 0.5, 0.0],
-                color: [0.2, 0.0, 0.3],
-            },
-            ColoredVertex {
-                position: [-0.5, -0.5, 0.0],
-                color: [0.2, 0.0, 0.3],
-            },
-            ColoredVertex {
-                position: [0.5, 0.5, 0.0],
-                color: [0.2, 0.0, 0.3],
-            },
-            ColoredVertex {
-                position: [0.5, -0.5, 0.0],
-                color: [0.2, 0.0, 0.3],
-            },
-        ])
-        .build();
-
-    let indices_buffer = BufferCreator::indices(&app_data.device)
-        .data(vec![0, 1, 2, 2, 1, 3])
-        .build();
+fn render(app_data: &AppData, state: &mut State, mut encoder: CommandEncoder, view: TextureView) {
+    let vertex_buffer = state.vertex_buffer.as_ref().unwrap();
+    let indices_buffer = state.indices_buffer.as_ref().unwrap();
 
     {
         let mut render_pass: RenderPass = RenderPassCreator::new(&view)
             .clear_color(Color::BLACK)
             .build(&mut encoder);
 
-        render_pass.set_pipeline(app_data.render_pipelines.get(0).unwrap());
+        render_pass.set_pipeline(&app_data.render_pipelines["rect"]);
 
         render_pass.set_vertex_buffer(0, vertex_buffer.slice());
-        render_pass.set_index_buffer(indices_buffer.slice(), wgpu::IndexFormat::Uint32);
 
-        render_pass.draw_indexed(0..indices_buffer.size(), 0, 0..1);
+        render_pass.draw_indices(indices_buffer);
     }
 
     app_data.queue.submit(once(encoder.finish()));
 }
 
-fn init(app_data: &AppData, _state: &mut (), vec: &mut Vec<RenderPipeline>) {
-    let render_pipeline = RenderPipelineCreator::from_shader_file(
+fn init(app_data: &AppData, state: &mut State, render_pipelines: &mut PipelineRegistry) {
+    state.vertex_buffer = Some(
+        BufferCreator::vertex(&app_data.device)
+            .data(vec![
+                ColoredVertex {
+                    position: [-0.5, 0.5, 0.0],
+                    color: [0.2, 0.0, 0.3],
+                },
+                ColoredVertex {
+                    position: [-0.5, -0.5, 0.0],
+                    color: [0.2, 0.0, 0.3],
+                },
+                ColoredVertex {
+                    position: [0.5, 0.5, 0.0],
+                    color: [0.2, 0.0, 0.3],
+                },
+                ColoredVertex {
+                    position: [0.5, -0.5, 0.0],
+                    color: [0.2, 0.0, 0.3],
+                },
+            ])
+            .build(),
+    );
+
+    state.indices_buffer = Some(
+        BufferCreator::indices(&app_data.device)
+            .data(vec![0u32, 1, 2, 2, 1, 3])
+            .build(),
+    );
+
+    RenderPipelineCreator::from_shader_file(
         "examples/shaderBasicColor.wgsl",
         &app_data.device,
-        &app_data.config,
+        app_data,
     )
     .add_vertex_buffer(ColoredVertex::descriptor())
-    .build();
-
-    vec.push(render_pipeline);
+    .build_watched("rect", app_data, render_pipelines);
 }
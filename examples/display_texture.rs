@@ -0,0 +1,116 @@
+use std::iter::once;
+
+use wgpu::{
+    BindGroup, Color, CommandEncoder, FilterMode, ShaderStages, TextureView, VertexAttribute,
+};
+
+use wgpu_noboiler::app::{AppCreator, AppData};
+use wgpu_noboiler::buffer::{BufferCreator, SimpleBuffer};
+use wgpu_noboiler::pipeline_registry::PipelineRegistry;
+use wgpu_noboiler::render_pass::{RenderPassCreator, RenderPassExt};
+use wgpu_noboiler::render_pipeline::RenderPipelineCreator;
+use wgpu_noboiler::texture::{SimpleTexture, TextureCreator};
+use wgpu_noboiler::vertex::Vertex;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct TexturedVertex {
+    position: [f32; 3],
+    tex_coords: [f32; 2],
+}
+
+impl Vertex<2> for TexturedVertex {
+    const ATTRIBS: [VertexAttribute; 2] = wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x2];
+}
+
+struct State {
+    texture_bind_group: Option<BindGroup>,
+    vertex_buffer: Option<SimpleBuffer>,
+    indices_buffer: Option<SimpleBuffer>,
+}
+
+fn main() {
+    AppCreator::new(State {
+        texture_bind_group: None,
+        vertex_buffer: None,
+        indices_buffer: None,
+    })
+    .init(init)
+    .render(render)
+    .title("Texture")
+    .run();
+}
+
+fn render(app_data: &AppData, state: &mut State, mut encoder: CommandEncoder, view: TextureView) {
+    let vertex_buffer = state.vertex_buffer.as_ref().unwrap();
+    let indices_buffer = state.indices_buffer.as_ref().unwrap();
+
+    {
+        let mut render_pass = RenderPassCreator::new(&view)
+            .clear_color(Color::BLACK)
+            .build(&mut encoder);
+
+        render_pass.set_pipeline(&app_data.render_pipelines["texture"]);
+        render_pass.set_bind_group(0, state.texture_bind_group.as_ref().unwrap(), &[]);
+
+        render_pass.set_vertex_buffer(0, vertex_buffer.slice());
+
+        render_pass.draw_indices(indices_buffer);
+    }
+
+    app_data.queue.submit(once(encoder.finish()));
+}
+
+fn init(app_data: &AppData, state: &mut State, render_pipelines: &mut PipelineRegistry) {
+    let texture = TextureCreator::from_bytes(
+        include_bytes!("checker.png"),
+        &app_data.device,
+        &app_data.queue,
+    )
+    .filter(FilterMode::Nearest)
+    .build();
+
+    let layout = SimpleTexture::bind_group_layout(&app_data.device, ShaderStages::FRAGMENT);
+
+    state.texture_bind_group = Some(texture.bind_group(&app_data.device, &layout));
+
+    state.vertex_buffer = Some(
+        BufferCreator::vertex(&app_data.device)
+            .data(vec![
+                TexturedVertex {
+                    position: [-0.5, 0.5, 0.0],
+                    tex_coords: [0.0, 0.0],
+                },
+                TexturedVertex {
+                    position: [-0.5, -0.5, 0.0],
+                    tex_coords: [0.0, 1.0],
+                },
+                TexturedVertex {
+                    position: [0.5, 0.5, 0.0],
+                    tex_coords: [1.0, 0.0],
+                },
+                TexturedVertex {
+                    position: [0.5, -0.5, 0.0],
+                    tex_coords: [1.0, 1.0],
+                },
+            ])
+            .build(),
+    );
+
+    state.indices_buffer = Some(
+        BufferCreator::indices(&app_data.device)
+            .data(vec![0u16, 1, 2, 2, 1, 3])
+            .build(),
+    );
+
+    let render_pipeline = RenderPipelineCreator::from_shader_file(
+        "examples/shaderTexture.wgsl",
+        &app_data.device,
+        app_data,
+    )
+    .add_vertex_buffer(TexturedVertex::descriptor())
+    .add_bind_group(layout.layout())
+    .build();
+
+    render_pipelines.insert("texture", render_pipeline);
+}
@@ -0,0 +1,77 @@
+use std::iter::once;
+
+use wgpu::{Color, CommandEncoder, TextureView, VertexAttribute};
+
+use wgpu_noboiler::app::{AppCreator, AppData};
+use wgpu_noboiler::pipeline_registry::PipelineRegistry;
+use wgpu_noboiler::render_pass::RenderPassCreator;
+use wgpu_noboiler::render_pipeline::RenderPipelineCreator;
+use wgpu_noboiler::vertex::Vertex;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct ColoredVertex {
+    position: [f32; 3],
+    color: [f32; 3],
+}
+
+impl Vertex<2> for ColoredVertex {
+    const ATTRIBS: [VertexAttribute; 2] = wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3];
+}
+
+const TRIANGLE: [ColoredVertex; 3] = [
+    ColoredVertex {
+        position: [0.0, 0.5, 0.0],
+        color: [1.0, 0.0, 0.0],
+    },
+    ColoredVertex {
+        position: [-0.5, -0.5, 0.0],
+        color: [0.0, 1.0, 0.0],
+    },
+    ColoredVertex {
+        position: [0.5, -0.5, 0.0],
+        color: [0.0, 0.0, 1.0],
+    },
+];
+
+fn main() {
+    let (app_data, _) = AppCreator::headless((), 256, 256)
+        .init(init)
+        .render(render)
+        .run_headless();
+
+    app_data
+        .capture_frame()
+        .unwrap()
+        .save(concat!(env!("CARGO_MANIFEST_DIR"), "/target/headless_screenshot.png"))
+        .unwrap();
+}
+
+fn render(app_data: &AppData, _: &mut (), mut encoder: CommandEncoder, view: TextureView) {
+    let vertex_buffer = app_data.upload(&TRIANGLE);
+
+    {
+        let mut render_pass = RenderPassCreator::new(&view)
+            .clear_color(Color::BLACK)
+            .build(&mut encoder);
+
+        render_pass.set_pipeline(&app_data.render_pipelines["triangle"]);
+        render_pass.set_vertex_buffer(0, vertex_buffer.slice());
+
+        render_pass.draw(0..TRIANGLE.len() as u32, 0..1);
+    }
+
+    app_data.queue.submit(once(encoder.finish()));
+}
+
+fn init(app_data: &AppData, _state: &mut (), render_pipelines: &mut PipelineRegistry) {
+    let render_pipeline = RenderPipelineCreator::from_shader_file(
+        "examples/shaderBasicColor.wgsl",
+        &app_data.device,
+        app_data,
+    )
+    .add_vertex_buffer(ColoredVertex::descriptor())
+    .build();
+
+    render_pipelines.insert("triangle", render_pipeline);
+}
@@ -0,0 +1,114 @@
+use std::iter::once;
+
+use wgpu::{Color, CommandEncoder, TextureView, VertexAttribute};
+
+use wgpu_noboiler::app::{AppCreator, AppData};
+use wgpu_noboiler::buffer::{BufferCreator, SimpleBuffer};
+use wgpu_noboiler::pipeline_registry::PipelineRegistry;
+use wgpu_noboiler::render_pass::{RenderPassCreator, RenderPassExt};
+use wgpu_noboiler::render_pipeline::RenderPipelineCreator;
+use wgpu_noboiler::vertex::Vertex;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct PosVertex {
+    position: [f32; 2],
+}
+
+impl Vertex<1> for PosVertex {
+    const ATTRIBS: [VertexAttribute; 1] = wgpu::vertex_attr_array![0 => Float32x2];
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct Instance {
+    offset: [f32; 2],
+    color: [f32; 3],
+}
+
+// the locations get shifted behind the ones of PosVertex by add_instance_buffer
+impl Vertex<2> for Instance {
+    const ATTRIBS: [VertexAttribute; 2] = wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x3];
+}
+
+const GRID_SIZE: u32 = 8;
+
+#[derive(Default)]
+struct State {
+    vertex_buffer: Option<SimpleBuffer>,
+    instance_buffer: Option<SimpleBuffer>,
+}
+
+fn main() {
+    AppCreator::new(State::default())
+        .init(init)
+        .render(render)
+        .title("Instancing")
+        .run();
+}
+
+fn render(app_data: &AppData, state: &mut State, mut encoder: CommandEncoder, view: TextureView) {
+    {
+        let mut render_pass = RenderPassCreator::new(&view)
+            .clear_color(Color::BLACK)
+            .build(&mut encoder);
+
+        render_pass.set_pipeline(&app_data.render_pipelines["instanced"]);
+
+        render_pass.draw_instanced(
+            state.vertex_buffer.as_ref().unwrap(),
+            state.instance_buffer.as_ref().unwrap(),
+        );
+    }
+
+    app_data.queue.submit(once(encoder.finish()));
+}
+
+fn init(app_data: &AppData, state: &mut State, render_pipelines: &mut PipelineRegistry) {
+    let size = 1.0 / GRID_SIZE as f32;
+
+    state.vertex_buffer = Some(
+        BufferCreator::vertex(&app_data.device)
+            .data(vec![
+                PosVertex {
+                    position: [0.0, size * 0.8],
+                },
+                PosVertex {
+                    position: [-size * 0.8, -size * 0.8],
+                },
+                PosVertex {
+                    position: [size * 0.8, -size * 0.8],
+                },
+            ])
+            .build(),
+    );
+
+    let instances = (0..GRID_SIZE * GRID_SIZE)
+        .map(|index| {
+            let (x, y) = (index % GRID_SIZE, index / GRID_SIZE);
+
+            Instance {
+                offset: [
+                    (x * 2 + 1) as f32 * size - 1.0,
+                    (y * 2 + 1) as f32 * size - 1.0,
+                ],
+                color: [x as f32 / GRID_SIZE as f32, y as f32 / GRID_SIZE as f32, 0.5],
+            }
+        })
+        .collect();
+
+    state.instance_buffer = Some(
+        BufferCreator::instance(&app_data.device)
+            .data(instances)
+            .build(),
+    );
+
+    RenderPipelineCreator::from_shader_file(
+        "examples/shaderInstanced.wgsl",
+        &app_data.device,
+        app_data,
+    )
+    .add_vertex_buffer(PosVertex::descriptor())
+    .add_instance_buffer(Instance::descriptor())
+    .build_watched("instanced", app_data, render_pipelines);
+}
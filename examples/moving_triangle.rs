@@ -1,9 +1,11 @@
 use std::iter::once;
 
-use wgpu::{CommandEncoder, PresentMode, RenderPipeline, TextureView, VertexAttribute};
+use wgpu::{CommandEncoder, PresentMode, TextureView, VertexAttribute};
+use winit::event::VirtualKeyCode;
 
 use wgpu_noboiler::app::{AppCreator, AppData};
-use wgpu_noboiler::buffer::BufferCreator;
+use wgpu_noboiler::buffer::{BufferCreator, DynamicBuffer};
+use wgpu_noboiler::pipeline_registry::PipelineRegistry;
 use wgpu_noboiler::render_pass::RenderPassCreator;
 use wgpu_noboiler::render_pipeline::RenderPipelineCreator;
 use wgpu_noboiler::vertex::Vertex;
@@ -22,6 +24,7 @@ struct State {
     pos: (f32, f32),
     vel: (f32, f32),
     x_scale: f32,
+    vertex_buffer: Option<DynamicBuffer<ColoredPosVertex>>,
 }
 
 const TRIANGLE_SIZE: f32 = 0.3;
@@ -31,18 +34,25 @@ fn main() {
         pos: (0.5, 0.0),
         vel: (0.707, 0.707),
         x_scale: 9.0 / 16.0,
+        vertex_buffer: None,
     })
         .init(init)
         .render(render)
         .update(update)
         .resize(resize)
         .present_mode(PresentMode::Immediate)
+        .target_fps(Some(144.0))
+        .sample_count(4)
         .run()
 }
 
 fn render(app_data: &AppData, state: &mut State, mut encoder: CommandEncoder, view: TextureView) {
-    let vertex_buffer = BufferCreator::vertex(&app_data.device)
-        .data(vec![
+    let vertex_buffer = state.vertex_buffer.as_mut().unwrap();
+
+    vertex_buffer.replace(
+        &app_data.device,
+        &app_data.queue,
+        vec![
             ColoredPosVertex {
                 position: [
                     (0.0 + state.pos.0) * state.x_scale,
@@ -61,22 +71,26 @@ fn render(app_data: &AppData, state: &mut State, mut encoder: CommandEncoder, vi
                     -TRIANGLE_SIZE + state.pos.1,
                 ],
             },
-        ])
-        .build();
+        ],
+    );
 
     {
-        let mut render_pass = RenderPassCreator::new(&view).build(&mut encoder);
+        let mut render_pass = RenderPassCreator::from_app(&view, app_data).build(&mut encoder);
 
-        render_pass.set_pipeline(app_data.render_pipelines.get(0).unwrap());
+        render_pass.set_pipeline(&app_data.render_pipelines["triangle"]);
         render_pass.set_vertex_buffer(0, vertex_buffer.slice());
 
-        render_pass.draw(0..3, 0..1);
+        render_pass.draw(0..vertex_buffer.size(), 0..1);
     }
 
     app_data.queue.submit(once(encoder.finish()));
 }
 
 fn update(app_data: &AppData, state: &mut State) {
+    if app_data.input.just_pressed(VirtualKeyCode::Space) {
+        state.vel = (-state.vel.0, -state.vel.1);
+    }
+
     if state.pos.0.abs() > 1.0 / state.x_scale - TRIANGLE_SIZE {
         state.vel.0 = -state.vel.0;
     }
@@ -93,14 +107,20 @@ fn resize(_: &AppData, state: &mut State, size: (u32, u32)) {
     state.x_scale = size.1 as f32 / size.0 as f32;
 }
 
-fn init(app_data: &AppData, _state: &mut State, vec: &mut Vec<RenderPipeline>) {
+fn init(app_data: &AppData, state: &mut State, render_pipelines: &mut PipelineRegistry) {
+    state.vertex_buffer = Some(
+        BufferCreator::vertex(&app_data.device)
+            .label("Triangle Buffer")
+            .build_dynamic(),
+    );
+
     let render_pipeline = RenderPipelineCreator::from_shader_file(
         "examples/shaderColorFromPos.wgsl",
         &app_data.device,
-        &app_data.config,
+        app_data,
     )
         .add_vertex_buffer(ColoredPosVertex::descriptor())
         .build();
 
-    vec.push(render_pipeline);
+    render_pipelines.insert("triangle", render_pipeline);
 }
@@ -0,0 +1,44 @@
+use std::iter::once;
+
+use wgpu::{Color, CommandEncoder, TextureView};
+use winit::event::VirtualKeyCode;
+use winit::window::WindowBuilder;
+
+use wgpu_noboiler::app::{AppCreator, AppData};
+use wgpu_noboiler::render_pass::RenderPassCreator;
+
+struct State {
+    opened_windows: u32,
+}
+
+fn main() {
+    AppCreator::new(State { opened_windows: 0 })
+        .title("Main (press N to open a window)")
+        .add_window("inspector", WindowBuilder::new().with_title("Inspector"))
+        .update(update)
+        .render(render)
+        .run();
+}
+
+fn update(app_data: &AppData, state: &mut State) {
+    if app_data.input.just_pressed(VirtualKeyCode::N) {
+        state.opened_windows += 1;
+
+        let name = format!("preview {}", state.opened_windows);
+        app_data.open_window(&name, WindowBuilder::new().with_title(&name));
+    }
+}
+
+fn render(app_data: &AppData, _: &mut State, mut encoder: CommandEncoder, view: TextureView) {
+    let clear_color = match app_data.window_name() {
+        "main" => Color::BLACK,
+        "inspector" => Color::BLUE,
+        _ => Color::GREEN,
+    };
+
+    RenderPassCreator::new(&view)
+        .clear_color(clear_color)
+        .build(&mut encoder);
+
+    app_data.queue.submit(once(encoder.finish()));
+}
@@ -0,0 +1,11 @@
+@group(0) @binding(0)
+var<storage, read_write> values: array<f32>;
+
+@compute @workgroup_size(64)
+fn cs_main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x >= arrayLength(&values)) {
+        return;
+    }
+
+    values[id.x] = values[id.x] * 2.0;
+}
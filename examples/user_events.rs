@@ -0,0 +1,64 @@
+use std::iter::once;
+use std::thread;
+use std::time::Duration;
+
+use wgpu::{Color, CommandEncoder, TextureView};
+
+use wgpu_noboiler::app::{AppCreator, AppData};
+use wgpu_noboiler::pipeline_registry::PipelineRegistry;
+use wgpu_noboiler::render_pass::RenderPassCreator;
+
+/// sent from the background thread
+enum Message {
+    Color(Color),
+}
+
+fn main() {
+    let app_creator = AppCreator::with_user_events(Color::BLACK)
+        .init(init)
+        .user_event(user_event)
+        .render(render)
+        .title("User Events");
+
+    let event_loop_proxy = app_creator.event_loop_proxy().unwrap();
+
+    thread::spawn(move || {
+        let colors = [Color::RED, Color::GREEN, Color::BLUE];
+
+        for color in colors.into_iter().cycle() {
+            thread::sleep(Duration::from_secs(1));
+
+            // fails once the event loop is closed
+            if event_loop_proxy.send_event(Message::Color(color)).is_err() {
+                break;
+            }
+        }
+    });
+
+    app_creator.run();
+}
+
+fn init(app_data: &AppData, _: &mut Color, _: &mut PipelineRegistry) {
+    let event_loop_proxy = app_data.event_loop_proxy::<Message>().unwrap();
+
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(500));
+        let _ = event_loop_proxy.send_event(Message::Color(Color::WHITE));
+    });
+}
+
+fn user_event(_: &AppData, color: &mut Color, message: Message) {
+    match message {
+        Message::Color(new_color) => *color = new_color,
+    }
+}
+
+fn render(app_data: &AppData, color: &mut Color, mut encoder: CommandEncoder, view: TextureView) {
+    {
+        RenderPassCreator::new(&view)
+            .clear_color(*color)
+            .build(&mut encoder);
+    }
+
+    app_data.queue.submit(once(encoder.finish()));
+}
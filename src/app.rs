@@ -1,49 +1,494 @@
-use std::time::Instant;
-
-use wgpu::{Adapter, CommandEncoder, CommandEncoderDescriptor, CompositeAlphaMode, Device, DeviceDescriptor, Instance, Limits, PowerPreference, PresentMode, Queue, RenderPipeline, RequestAdapterOptions, Surface, SurfaceConfiguration, SurfaceError, TextureFormat, TextureUsages, TextureView, TextureViewDescriptor};
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::hint::spin_loop;
+use std::iter::once;
+use std::mem;
+use std::time::{Duration, Instant};
+
+use bytemuck::Pod;
+use image::RgbaImage;
+use wgpu::{Adapter, CommandEncoder, CommandEncoderDescriptor, CompositeAlphaMode, Device, DeviceDescriptor, Extent3d, Features, Instance, Limits, Maintain, PowerPreference, PresentMode, Queue, RequestAdapterOptions, Surface, SurfaceConfiguration, SurfaceError, Texture, TextureDescriptor, TextureDimension, TextureFormat, TextureFormatFeatureFlags, TextureUsages, TextureView, TextureViewDescriptor};
 use winit::dpi::PhysicalSize;
 use winit::event::{Event, WindowEvent};
-use winit::event_loop::{ControlFlow, EventLoop};
-use winit::window::{Window, WindowBuilder};
-
-pub struct App<T: 'static> {
+use winit::event_loop::{
+    ControlFlow, EventLoop, EventLoopBuilder, EventLoopProxy, EventLoopWindowTarget,
+};
+use winit::window::{Window, WindowBuilder, WindowId};
+
+use crate::capture::{capture_texture, FrameBlit};
+use crate::error::{NoboilerError, RunError};
+use crate::frame_stats::{FrameStats, FrameTiming};
+use crate::hot_reload::ShaderWatcher;
+use crate::input::InputState;
+use crate::pipeline_registry::PipelineRegistry;
+use crate::upload::{TransientSlice, UploadAllocator, UploadStats};
+
+/// how long before a [limited](AppCreator::target_fps) frame the event loop stops sleeping and spins
+const SPIN_DURATION: Duration = Duration::from_millis(2);
+
+pub struct App<T: 'static, E: 'static = ()> {
     state: T,
     app_data: AppData,
 
-    window_event_fn: Option<WindowEventFn<T>>,
-    resize_fn: Option<ResizeFn<T>>,
-    update_fn: Option<UpdateFn<T>>,
-    render_fn: Option<RenderFn<T>>,
-    init_fn: Option<InitFn<T>>,
+    window_event_fn: Option<BoxedWindowEventFn<T>>,
+    resize_fn: Option<BoxedResizeFn<T>>,
+    update_fn: Option<BoxedUpdateFn<T>>,
+    fixed_update_fn: Option<BoxedFixedUpdateFn<T>>,
+    render_fn: Option<BoxedRenderFn<T>>,
+    init_fn: Option<BoxedInitFn<T>>,
+    user_event_fn: Option<BoxedUserEventFn<T, E>>,
 }
 
 /// background data for your [App]
 pub struct AppData {
     last_frame_instant: Instant,
-    render_instant: Instant,
 
-    ///Avg Frames Per Seconds of the last 30 Frames
+    ///Avg Frames Per Seconds of the frames in [AppData::frame_stats]
     pub fps: f64,
     ///time since the last frame in seconds
     pub delta_time: f64,
     ///duration of the the last renderFunction in seconds
     pub render_time: f64,
-    ///duration of the the last renderFunction in seconds
+    ///duration of the the last updateFunction in seconds
     pub update_time: f64,
+    ///frame | update | render durations of the last frames
+    pub frame_stats: FrameStats,
 
-    surface: Surface,
+    ///duration of one [fixed update](AppCreator::fixed_update) step in seconds
+    pub fixed_delta_time: f64,
+    ///how far (0..1) the time of the frame is between the last and the next [fixed update](AppCreator::fixed_update)
+    ///
+    /// use it to interpolate between the last two fixed states while rendering
+    pub interpolation_alpha: f64,
+    fixed_update_accumulator: f64,
+    max_fixed_steps: u32,
+
+    target: RenderTarget,
+    window: Option<Window>,
+    window_name: String,
+    /// only with [AppCreator::capture_frames]
+    frame_blit: Option<FrameBlit>,
 
     pub device: Device,
     pub queue: Queue,
+    ///configuration of the surface of the window which is currently handled
     pub config: SurfaceConfiguration,
 
+    ///size of the window which is currently handled
     pub size: PhysicalSize<u32>,
 
-    ///vec of all RenderPipelines which got [created](AppCreator::init)
-    pub render_pipelines: Vec<RenderPipeline>,
+    ///all RenderPipelines which got [created](AppCreator::init) or added later by name
+    pub render_pipelines: PipelineRegistry,
+
+    ///keyboard | mouse state of the window which is currently handled, updated before every frame
+    ///
+    /// every window has its own, in [AppCreator::update] it is the one of the main window
+    pub input: InputState,
+
+    upload_allocator: RefCell<UploadAllocator>,
+
+    target_fps: Cell<Option<f64>>,
+
+    watch_shaders: bool,
+    pub(crate) shader_watcher: RefCell<ShaderWatcher>,
+
+    depth_buffer: Option<DepthBuffer>,
+
+    sample_count: u32,
+    msaa_buffer: Option<MsaaBuffer>,
+
+    /// the additional windows, except the one which is currently handled
+    windows: Vec<WindowSurface>,
+    pending_windows: RefCell<Vec<(String, WindowBuilder)>>,
+
+    instance: Instance,
+    adapter: Adapter,
+
+    event_loop_proxy: Option<Box<dyn Any>>,
 }
 
-impl<T: 'static> App<T> {
+/// name of the window the [App] was created with
+const MAIN_WINDOW_NAME: &str = "main";
+
+/// everything which belongs to one window, the one which is currently handled lives in the [AppData]
+struct WindowSurface {
+    target: RenderTarget,
+    window: Option<Window>,
+    name: String,
+
+    config: SurfaceConfiguration,
+    size: PhysicalSize<u32>,
+
+    depth_buffer: Option<DepthBuffer>,
+    msaa_buffer: Option<MsaaBuffer>,
+
+    input: InputState,
+}
+
+/// depth texture which gets recreated with the [Surface], see [AppCreator::depth_buffer]
+struct DepthBuffer {
+    format: TextureFormat,
+    texture: Texture,
+    view: TextureView,
+}
+
+impl DepthBuffer {
+    fn new(
+        device: &Device,
+        config: &SurfaceConfiguration,
+        format: TextureFormat,
+        sample_count: u32,
+    ) -> DepthBuffer {
+        let usage = if sample_count == 1 {
+            TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING
+        } else {
+            TextureUsages::RENDER_ATTACHMENT
+        };
+
+        let texture = create_attachment(
+            device,
+            config,
+            "Depth Texture",
+            format,
+            sample_count,
+            usage,
+        );
+        let view = texture.create_view(&TextureViewDescriptor::default());
+
+        DepthBuffer {
+            format,
+            texture,
+            view,
+        }
+    }
+}
+
+/// multisampled color texture which gets resolved into the frame, see [AppCreator::sample_count]
+struct MsaaBuffer {
+    texture: Texture,
+    view: TextureView,
+}
+
+impl MsaaBuffer {
+    fn new(device: &Device, config: &SurfaceConfiguration, sample_count: u32) -> MsaaBuffer {
+        let texture = create_attachment(
+            device,
+            config,
+            "Multisampled Texture",
+            config.format,
+            sample_count,
+            TextureUsages::RENDER_ATTACHMENT,
+        );
+        let view = texture.create_view(&TextureViewDescriptor::default());
+
+        MsaaBuffer { texture, view }
+    }
+}
+
+/// what the frames of an [App] get rendered into
+enum RenderTarget {
+    Surface(Surface),
+    /// offscreen texture used in [headless](AppCreator::headless) mode
+    Texture(Texture),
+}
+
+impl AppData {
+    /// the offscreen [Texture] frames get rendered into
+    ///
+    /// only available when created with [AppCreator::headless]
+    pub fn offscreen_texture(&self) -> Option<&Texture> {
+        match &self.target {
+            RenderTarget::Surface(_) => None,
+            RenderTarget::Texture(texture) => Some(texture),
+        }
+    }
+
+    /// copies the last rendered frame back to the cpu
+    ///
+    /// available when created with [AppCreator::headless] or with a window when [AppCreator::capture_frames] is enabled (then it is the last frame of the main window),
+    /// see [capture_texture] for details
+    pub fn capture_frame(&self) -> Option<RgbaImage> {
+        let texture = match &self.target {
+            RenderTarget::Texture(texture) => Some(texture),
+            RenderTarget::Surface(_) => self
+                .frame_blit
+                .as_ref()
+                .and_then(FrameBlit::last_frame),
+        };
+
+        texture.map(|texture| capture_texture(&self.device, &self.queue, texture))
+    }
+
+    /// the [TextureView] of the depth buffer
+    ///
+    /// only available when enabled with [AppCreator::depth_buffer], use it with [RenderPassCreator::with_depth](crate::render_pass::RenderPassCreator::with_depth)
+    pub fn depth_view(&self) -> Option<&TextureView> {
+        self.depth_buffer.as_ref().map(|depth_buffer| &depth_buffer.view)
+    }
+
+    /// the depth [Texture], has the same size as the [Surface]
+    pub fn depth_texture(&self) -> Option<&Texture> {
+        self.depth_buffer
+            .as_ref()
+            .map(|depth_buffer| &depth_buffer.texture)
+    }
+
+    /// the [TextureFormat] of the depth buffer set with [AppCreator::depth_buffer]
+    pub fn depth_format(&self) -> Option<TextureFormat> {
+        self.depth_buffer
+            .as_ref()
+            .map(|depth_buffer| depth_buffer.format)
+    }
+
+    /// the sample count set with [AppCreator::sample_count]
+    ///
+    /// pipelines created with the [AppData] as target use it by default
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// the [TextureView] of the multisampled color texture which gets resolved into the frame
+    ///
+    /// only available when the [sample count](AppCreator::sample_count) is greater than 1, use it with [RenderPassCreator::with_msaa](crate::render_pass::RenderPassCreator::with_msaa)
+    pub fn msaa_view(&self) -> Option<&TextureView> {
+        self.msaa_buffer.as_ref().map(|msaa_buffer| &msaa_buffer.view)
+    }
+
+    /// the multisampled color [Texture], has the same size as the [Surface]
+    pub fn msaa_texture(&self) -> Option<&Texture> {
+        self.msaa_buffer
+            .as_ref()
+            .map(|msaa_buffer| &msaa_buffer.texture)
+    }
+
+    /// name of the window which is currently handled
+    ///
+    /// the window the [App] was created with is called `"main"` (also in [headless](AppCreator::headless) mode),
+    /// the others are named by [AppCreator::add_window] | [AppData::open_window]
+    pub fn window_name(&self) -> &str {
+        &self.window_name
+    }
+
+    /// the window which is currently handled, [None] in [headless](AppCreator::headless) mode
+    pub fn window(&self) -> Option<&Window> {
+        self.window.as_ref()
+    }
+
+    /// the [WindowId] of the window which is currently handled
+    pub fn window_id(&self) -> Option<WindowId> {
+        self.window.as_ref().map(Window::id)
+    }
+
+    /// names of all open windows
+    pub fn window_names(&self) -> impl Iterator<Item = &str> {
+        once(self.window_name.as_str())
+            .chain(self.windows.iter().map(|window_surface| window_surface.name.as_str()))
+    }
+
+    /// writes `data` into a buffer which is shared by all uploads of the frame, instead of creating a new [Buffer](wgpu::Buffer)
+    ///
+    /// the returned [TransientSlice] can be used as vertex | index | uniform buffer (it is aligned to [min_uniform_buffer_offset_alignment](wgpu::Limits::min_uniform_buffer_offset_alignment)).
+    /// it is only valid in the current frame, the memory gets reused once the gpu finished the work submitted in this frame
+    ///
+    /// # Panics
+    ///
+    /// if `data` is empty
+    pub fn upload<T: Pod>(&self, data: &[T]) -> TransientSlice {
+        self.upload_allocator
+            .borrow_mut()
+            .upload(&self.device, &self.queue, data)
+    }
+
+    /// memory usage of [AppData::upload]
+    pub fn upload_stats(&self) -> UploadStats {
+        self.upload_allocator.borrow().stats()
+    }
+
+    /// returns an [EventLoopProxy] which sends events to [AppCreator::user_event] (also from other threads)
+    ///
+    /// only works with the same `E` the [AppCreator] was created with (see [AppCreator::with_user_events]),
+    /// for any other type [None] is returned and an error gets logged. in [headless](AppCreator::headless) mode it is always [None]
+    pub fn event_loop_proxy<E: 'static>(&self) -> Option<EventLoopProxy<E>> {
+        let proxy = self
+            .event_loop_proxy
+            .as_ref()?
+            .downcast_ref::<EventLoopProxy<E>>()
+            .cloned();
+
+        if proxy.is_none() {
+            log::error!(
+                "event_loop_proxy::<{}>() does not match the user event type of the App",
+                std::any::type_name::<E>()
+            );
+        }
+
+        proxy
+    }
+
+    /// opens an additional window before the next frame
+    ///
+    /// every window has its own surface (and depth | msaa textures) but shares the [Device] and [Queue].
+    /// all callbacks get called for it as well, [AppData::window_name] tells which window is handled.
+    /// the surface uses the [TextureFormat] of the main window if possible so the same [RenderPipelines](wgpu::RenderPipeline) can be used.
+    /// closing it only closes the window, closing the main window exits the [App]
+    ///
+    /// errors (e.g. a name which is already used) get logged, does nothing in [headless](AppCreator::headless) mode
+    pub fn open_window(&self, name: &str, window_builder: WindowBuilder) {
+        self.pending_windows
+            .borrow_mut()
+            .push((name.to_owned(), window_builder));
+    }
+
+    fn add_window(&mut self, name: String, window: Window) -> Result<(), NoboilerError> {
+        let surface = unsafe { self.instance.create_surface(&window) }
+            .map_err(NoboilerError::SurfaceCreation)?;
+
+        if !self.adapter.is_surface_supported(&surface) {
+            return Err(NoboilerError::SurfaceUnsupported);
+        }
+
+        let formats = surface.get_capabilities(&self.adapter).formats;
+
+        let format = if formats.contains(&self.config.format) {
+            self.config.format
+        } else {
+            *formats.first().ok_or(NoboilerError::SurfaceUnsupported)?
+        };
+
+        let size = window.inner_size();
+
+        let config = SurfaceConfiguration {
+            format,
+            width: size.width.max(1),
+            height: size.height.max(1),
+            ..self.config.clone()
+        };
+
+        surface.configure(&self.device, &config);
+
+        let depth_buffer = self
+            .depth_format()
+            .map(|format| DepthBuffer::new(&self.device, &config, format, self.sample_count));
+
+        let msaa_buffer = (self.sample_count != 1)
+            .then(|| MsaaBuffer::new(&self.device, &config, self.sample_count));
+
+        self.windows.push(WindowSurface {
+            target: RenderTarget::Surface(surface),
+            window: Some(window),
+            name,
+            config,
+            size,
+            depth_buffer,
+            msaa_buffer,
+            input: InputState::default(),
+        });
+
+        Ok(())
+    }
+
+    fn close_window(&mut self, window_id: WindowId) {
+        self.windows.retain(|window_surface| {
+            window_surface.window.as_ref().map(Window::id) != Some(window_id)
+        });
+    }
+
+    /// swaps the window at the index with the one which is currently in the [AppData]
+    fn swap_window(&mut self, index: usize) {
+        let window_surface = &mut self.windows[index];
+
+        mem::swap(&mut self.target, &mut window_surface.target);
+        mem::swap(&mut self.window, &mut window_surface.window);
+        mem::swap(&mut self.window_name, &mut window_surface.name);
+        mem::swap(&mut self.config, &mut window_surface.config);
+        mem::swap(&mut self.size, &mut window_surface.size);
+        mem::swap(&mut self.depth_buffer, &mut window_surface.depth_buffer);
+        mem::swap(&mut self.msaa_buffer, &mut window_surface.msaa_buffer);
+        mem::swap(&mut self.input, &mut window_surface.input);
+    }
+
+    /// the frame rate the [App] is limited to, see [AppCreator::target_fps]
+    pub fn target_fps(&self) -> Option<f64> {
+        self.target_fps.get()
+    }
+
+    /// changes the frame rate the [App] is limited to, [None] (or a value <= 0) renders as fast as possible (or as the [PresentMode] allows)
+    pub fn set_target_fps(&self, target_fps: Option<f64>) {
+        self.target_fps.set(target_fps);
+    }
+
+    /// when the next frame should start, [None] if the frame rate is not limited
+    fn next_frame_instant(&self) -> Option<Instant> {
+        let target_fps = self.target_fps.get().filter(|target_fps| *target_fps > 0.0)?;
+
+        Some(self.last_frame_instant + Duration::from_secs_f64(1.0 / target_fps))
+    }
+
+    fn configure_target(&mut self) {
+        match &mut self.target {
+            RenderTarget::Surface(surface) => surface.configure(&self.device, &self.config),
+            RenderTarget::Texture(texture) => {
+                *texture = create_offscreen_texture(&self.device, &self.config)
+            }
+        }
+
+        if let Some(depth_buffer) = &mut self.depth_buffer {
+            *depth_buffer = DepthBuffer::new(
+                &self.device,
+                &self.config,
+                depth_buffer.format,
+                self.sample_count,
+            );
+        }
+
+        if let Some(msaa_buffer) = &mut self.msaa_buffer {
+            *msaa_buffer = MsaaBuffer::new(&self.device, &self.config, self.sample_count);
+        }
+    }
+}
+
+fn create_attachment(
+    device: &Device,
+    config: &SurfaceConfiguration,
+    label: &str,
+    format: TextureFormat,
+    sample_count: u32,
+    usage: TextureUsages,
+) -> Texture {
+    device.create_texture(&TextureDescriptor {
+        label: Some(label),
+        size: Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count,
+        dimension: TextureDimension::D2,
+        format,
+        usage,
+        view_formats: &[],
+    })
+}
+
+fn create_offscreen_texture(device: &Device, config: &SurfaceConfiguration) -> Texture {
+    device.create_texture(&TextureDescriptor {
+        label: Some("Offscreen Texture"),
+        size: Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: config.format,
+        usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+        view_formats: &config.view_formats,
+    })
+}
+
+impl<T: 'static, E: 'static> App<T, E> {
     fn resize(&mut self, new_size: PhysicalSize<u32>) {
         if new_size.width == 0 || new_size.height == 0 {
             return;
@@ -52,34 +497,63 @@ impl<T: 'static> App<T> {
         self.app_data.size = new_size;
         self.app_data.config.width = new_size.width;
         self.app_data.config.height = new_size.height;
-        self.app_data
-            .surface
-            .configure(&self.app_data.device, &self.app_data.config);
+        self.app_data.configure_target();
 
-        if self.resize_fn.is_some() {
-            self.resize_fn.unwrap()(&self.app_data, &mut self.state, (self.app_data.size.width, self.app_data.size.height))
+        if let Some(resize_fn) = &mut self.resize_fn {
+            resize_fn(&self.app_data, &mut self.state, (self.app_data.size.width, self.app_data.size.height))
         }
     }
 
     fn init(&mut self) {
-        if self.init_fn.is_none() {
+        let Some(init_fn) = &mut self.init_fn else {
             return;
-        }
+        };
 
-        let mut render_pipelines = Vec::new();
-        self.init_fn.unwrap()(&self.app_data, &mut self.state, &mut render_pipelines);
+        let mut render_pipelines = PipelineRegistry::new();
+        init_fn(&self.app_data, &mut self.state, &mut render_pipelines);
         self.app_data.render_pipelines = render_pipelines
     }
 
+    /// swaps in pipelines which got added or reloaded since the last frame
+    fn update_pipelines(&mut self) {
+        self.app_data.render_pipelines.apply_deferred();
+
+        if self.app_data.watch_shaders {
+            self.app_data
+                .shader_watcher
+                .get_mut()
+                .reload(&self.app_data.device, &mut self.app_data.render_pipelines);
+        }
+    }
+
     fn render(&mut self) -> Result<(), SurfaceError> {
-        if self.render_fn.is_none() {
+        let Some(render_fn) = &mut self.render_fn else {
             return Ok(());
-        }
+        };
+
+        let output = match &self.app_data.target {
+            RenderTarget::Surface(surface) => Some(surface.get_current_texture()?),
+            RenderTarget::Texture(_) => None,
+        };
+
+        let app_data = &mut self.app_data;
+        let capture = app_data.frame_blit.is_some() && app_data.window_name == MAIN_WINDOW_NAME;
+
+        let view = match (&output, &app_data.target) {
+            // the main window renders into the frame texture which gets copied onto the surface afterwards
+            (Some(_), _) if capture => app_data
+                .frame_blit
+                .as_mut()
+                .unwrap()
+                .frame(&app_data.device, &app_data.config)
+                .create_view(&TextureViewDescriptor::default()),
+            (Some(output), _) => output.texture.create_view(&TextureViewDescriptor::default()),
+            (None, RenderTarget::Texture(texture)) => {
+                texture.create_view(&TextureViewDescriptor::default())
+            }
+            (None, RenderTarget::Surface(_)) => unreachable!(),
+        };
 
-        let output = self.app_data.surface.get_current_texture()?;
-        let view = output
-            .texture
-            .create_view(&TextureViewDescriptor::default());
         let encoder = self
             .app_data
             .device
@@ -87,98 +561,321 @@ impl<T: 'static> App<T> {
                 label: Some("Render Encoder"),
             });
 
-        self.render_fn.unwrap()(&self.app_data, &mut self.state, encoder, view);
+        render_fn(&self.app_data, &mut self.state, encoder, view);
 
-        output.present();
+        if let Some(output) = output {
+            if let Some(frame_blit) = self.app_data.frame_blit.as_ref().filter(|_| capture) {
+                frame_blit.blit(
+                    &self.app_data.device,
+                    &self.app_data.queue,
+                    &output.texture.create_view(&TextureViewDescriptor::default()),
+                );
+            }
+
+            output.present();
+        }
 
         Ok(())
     }
 
-    fn run(mut self, window: Window, event_loop: EventLoop<()>) {
+    fn update(&mut self) {
+        let update_instant = Instant::now();
+
+        self.fixed_update();
+
+        if let Some(update_fn) = &mut self.update_fn {
+            update_fn(&self.app_data, &mut self.state);
+        }
+
+        self.app_data.update_time = update_instant.elapsed().as_secs_f64();
+    }
+
+    /// runs as many fixed steps as fit into the time since the last frame, at most [AppCreator::max_fixed_steps]
+    fn fixed_update(&mut self) {
+        let Some(fixed_update_fn) = &mut self.fixed_update_fn else {
+            return;
+        };
+
+        let app_data = &mut self.app_data;
+        let step = app_data.fixed_delta_time;
+
+        app_data.fixed_update_accumulator += app_data.delta_time;
+
+        let mut steps = 0;
+
+        while app_data.fixed_update_accumulator >= step {
+            if steps == app_data.max_fixed_steps {
+                // the updates can not keep up, skip the missing time instead of falling further behind
+                log::warn!(
+                    "skipped {:.3}s of fixed updates",
+                    app_data.fixed_update_accumulator - app_data.fixed_update_accumulator % step
+                );
+                app_data.fixed_update_accumulator %= step;
+                break;
+            }
+
+            fixed_update_fn(app_data, &mut self.state);
+
+            app_data.fixed_update_accumulator -= step;
+            steps += 1;
+        }
+
+        app_data.interpolation_alpha = app_data.fixed_update_accumulator / step;
+    }
+
+    /// records the timings of the frame and resets the per-frame input
+    fn end_frame(&mut self) {
+        let app_data = &mut self.app_data;
+
+        app_data.frame_stats.push(FrameTiming {
+            frame: app_data.delta_time,
+            update: app_data.update_time,
+            render: app_data.render_time,
+        });
+        app_data.fps = app_data.frame_stats.fps();
+
+        app_data.input.end_frame();
+        for window_surface in &mut app_data.windows {
+            window_surface.input.end_frame();
+        }
+
+        app_data
+            .upload_allocator
+            .get_mut()
+            .end_frame(&app_data.device, &app_data.queue);
+    }
+
+    fn run_headless(mut self, frames: u32, delta_time: f64) -> (AppData, T) {
+        self.init();
+
+        for _ in 0..frames {
+            self.app_data.delta_time = delta_time;
+
+            self.update_pipelines();
+            self.update();
+
+            let render_instant = Instant::now();
+            self.render()
+                .expect("headless rendering does not use a surface");
+            self.app_data.render_time = render_instant.elapsed().as_secs_f64();
+
+            self.end_frame();
+        }
+
+        self.app_data.device.poll(Maintain::Wait);
+
+        (self.app_data, self.state)
+    }
+
+    fn run(mut self, window: Window, event_loop: EventLoop<E>) {
+        self.app_data.window = Some(window);
+
+        self.open_pending_windows(&event_loop);
         self.init();
 
-        window.set_visible(true);
+        // only shown once init is done, so no blank window is visible while the shaders compile
+        if let Some(window) = &self.app_data.window {
+            window.set_visible(true);
+        }
+
+        // the setup should not count as the time of the first frame
+        self.app_data.last_frame_instant = Instant::now();
 
-        event_loop.run(move |event, _, control_flow| match event {
+        event_loop.run(move |event, event_loop, control_flow| match event {
             Event::WindowEvent {
                 ref event,
                 window_id,
             } => {
-                if window_id != window.id() {
+                if let WindowEvent::CloseRequested = event {
+                    if self.app_data.window_id() == Some(window_id) {
+                        *control_flow = ControlFlow::Exit;
+                    } else {
+                        self.app_data.close_window(window_id);
+                    }
                     return;
                 }
-                match event {
-                    WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
-                    WindowEvent::Resized(physical_size) => {
-                        self.resize(*physical_size);
-                    }
-                    WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
-                        self.resize(**new_inner_size);
-                    }
-                    _ => {
-                        if self.window_event_fn.is_some() {
-                            self.window_event_fn.unwrap()(&self.app_data, &mut self.state, event);
+
+                self.with_window(window_id, |app| {
+                    app.app_data.input.handle_event(event);
+
+                    match event {
+                        WindowEvent::Resized(physical_size) => {
+                            app.resize(*physical_size);
+                        }
+                        WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+                            app.resize(**new_inner_size);
+                        }
+                        _ => {
+                            if let Some(window_event_fn) = &mut app.window_event_fn {
+                                window_event_fn(&app.app_data, &mut app.state, event);
+                            }
                         }
                     }
-                }
+                });
             }
             Event::RedrawRequested(window_id) => {
-                if window_id != window.id() {
+                // every window gets rendered in the frame of the main window
+                if self.app_data.window_id() != Some(window_id) {
                     return;
                 }
 
-                self.app_data.render_time = self.app_data.render_instant.elapsed().as_secs_f64();
-
                 self.app_data.delta_time = self.app_data.last_frame_instant.elapsed().as_secs_f64();
-                self.app_data.fps = 1.0 / self.app_data.delta_time;
-
                 self.app_data.last_frame_instant = Instant::now();
 
-                let update_instant = Instant::now();
+                self.update_pipelines();
+                self.update();
+
+                let render_instant = Instant::now();
 
-                if self.update_fn.is_some() {
-                    self.update_fn.unwrap()(&self.app_data, &mut self.state);
+                self.render_window(control_flow);
+
+                for index in 0..self.app_data.windows.len() {
+                    self.app_data.swap_window(index);
+                    self.render_window(control_flow);
+                    self.app_data.swap_window(index);
                 }
 
-                self.app_data.update_time = update_instant.elapsed().as_secs_f64();
-                self.app_data.render_instant = Instant::now();
+                self.app_data.render_time = render_instant.elapsed().as_secs_f64();
 
-                match self.render() {
-                    Ok(_) => {}
-                    Err(SurfaceError::Lost) => self.resize(self.app_data.size),
-                    Err(SurfaceError::OutOfMemory) => *control_flow = ControlFlow::Exit,
-                    Err(e) => eprintln!("{:?}", e),
+                self.end_frame();
+            }
+            Event::UserEvent(event) => {
+                if let Some(user_event_fn) = &mut self.user_event_fn {
+                    user_event_fn(&self.app_data, &mut self.state, event);
                 }
             }
             Event::MainEventsCleared => {
-                window.request_redraw();
+                self.open_pending_windows(event_loop);
+
+                let Some(window) = &self.app_data.window else {
+                    return;
+                };
+
+                match self.app_data.next_frame_instant() {
+                    None => {
+                        *control_flow = ControlFlow::Poll;
+                        window.request_redraw();
+                    }
+                    Some(next_frame_instant) => {
+                        let now = Instant::now();
+
+                        if next_frame_instant <= now {
+                            *control_flow = ControlFlow::Poll;
+                            window.request_redraw();
+                        } else if next_frame_instant - now > SPIN_DURATION {
+                            // sleeping is not accurate enough, so the last part gets spun
+                            *control_flow =
+                                ControlFlow::WaitUntil(next_frame_instant - SPIN_DURATION);
+                        } else {
+                            while Instant::now() < next_frame_instant {
+                                spin_loop();
+                            }
+
+                            *control_flow = ControlFlow::Poll;
+                            window.request_redraw();
+                        }
+                    }
+                }
             }
 
             _ => {}
         });
     }
+
+    /// renders the window which is currently in the [AppData] and handles surface errors
+    fn render_window(&mut self, control_flow: &mut ControlFlow) {
+        match self.render() {
+            Ok(_) => {}
+            Err(SurfaceError::Lost) => self.resize(self.app_data.size),
+            Err(SurfaceError::OutOfMemory) => *control_flow = ControlFlow::Exit,
+            Err(e) => eprintln!("{:?}", e),
+        }
+    }
+
+    /// calls `f` while the window is swapped into the [AppData], does nothing for unknown windows
+    fn with_window(&mut self, window_id: WindowId, f: impl FnOnce(&mut Self)) {
+        if self.app_data.window_id() == Some(window_id) {
+            f(self);
+            return;
+        }
+
+        let Some(index) = self.app_data.windows.iter().position(|window_surface| {
+            window_surface.window.as_ref().map(Window::id) == Some(window_id)
+        }) else {
+            return;
+        };
+
+        self.app_data.swap_window(index);
+        f(self);
+        self.app_data.swap_window(index);
+    }
+
+    /// opens the windows which got requested with [AppCreator::add_window] | [AppData::open_window]
+    fn open_pending_windows(&mut self, event_loop: &EventLoopWindowTarget<E>) {
+        let pending_windows = self.app_data.pending_windows.take();
+
+        for (name, window_builder) in pending_windows {
+            if self.app_data.window_names().any(|window_name| window_name == name) {
+                log::error!("could not open window {}: the name is already used", name);
+                continue;
+            }
+
+            let result = window_builder
+                .build(event_loop)
+                .map_err(NoboilerError::WindowCreation)
+                .and_then(|window| self.app_data.add_window(name.clone(), window));
+
+            if let Err(err) = result {
+                log::error!("could not open window {}: {}", name, err);
+            }
+        }
+    }
 }
 
 /// builder for [App]
-pub struct AppCreator<T: 'static> {
+pub struct AppCreator<T: 'static, E: 'static = ()> {
     state: T,
 
-    window: Window,
-    event_loop: EventLoop<()>,
+    target: TargetCreator<E>,
 
-    window_event_fn: Option<WindowEventFn<T>>,
-    resize_fn: Option<ResizeFn<T>>,
-    update_fn: Option<UpdateFn<T>>,
-    render_fn: Option<RenderFn<T>>,
-    init_fn: Option<InitFn<T>>,
+    window_event_fn: Option<BoxedWindowEventFn<T>>,
+    resize_fn: Option<BoxedResizeFn<T>>,
+    update_fn: Option<BoxedUpdateFn<T>>,
+    fixed_update_fn: Option<BoxedFixedUpdateFn<T>>,
+    render_fn: Option<BoxedRenderFn<T>>,
+    init_fn: Option<BoxedInitFn<T>>,
+    user_event_fn: Option<BoxedUserEventFn<T, E>>,
 
     present_mode: PresentMode,
     power_preference: PowerPreference,
     device_limits: Limits,
+    force_fallback_adapter: bool,
+    watch_shaders: bool,
+    capture_frames: bool,
+    fixed_delta_time: f64,
+    max_fixed_steps: u32,
+    frame_stats_capacity: usize,
+    target_fps: Option<f64>,
+    windows: Vec<(String, WindowBuilder)>,
+    depth_format: Option<TextureFormat>,
+    sample_count: u32,
 
     view_formats: Vec<TextureFormat>,
 }
 
+#[allow(clippy::large_enum_variant)]
+enum TargetCreator<E: 'static> {
+    Window {
+        window: Window,
+        event_loop: EventLoop<E>,
+    },
+    Headless {
+        size: PhysicalSize<u32>,
+        frames: u32,
+        delta_time: f64,
+    },
+}
+
 impl<T: 'static> AppCreator<T> {
     /// creates [AppCreator]
     ///
@@ -187,26 +884,91 @@ impl<T: 'static> AppCreator<T> {
     /// * `state`: data which describes your App can be changes on [update](AppCreator::update) and used to [render](AppCreator::render)
     ///
     pub fn new(state: T) -> AppCreator<T> {
-        let event_loop = EventLoop::new();
+        Self::try_new(state).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// same as [AppCreator::new] but returns an [NoboilerError] if the window could not be created
+    pub fn try_new(state: T) -> Result<AppCreator<T>, NoboilerError> {
+        Self::try_with_user_events(state)
+    }
+
+    /// creates [AppCreator] which renders into an offscreen [Texture] instead of a window
+    ///
+    /// [run](AppCreator::run) calls [init](AppCreator::init) and then [update](AppCreator::update) | [render](AppCreator::render) for a fixed number of [frames](AppCreator::frames).
+    /// no window | display is needed, so this also works on CI and build servers
+    ///
+    /// # Arguments
+    ///
+    /// * `state`: data which describes your App can be changes on [update](AppCreator::update) and used to [render](AppCreator::render)
+    /// * `width`: width of the offscreen texture in pixels
+    /// * `height`: height of the offscreen texture in pixels
+    ///
+    pub fn headless(state: T, width: u32, height: u32) -> AppCreator<T> {
+        Self::with_target(
+            state,
+            TargetCreator::Headless {
+                size: PhysicalSize::new(width, height),
+                frames: 1,
+                delta_time: 1.0 / 60.0,
+            },
+        )
+    }
+}
+
+impl<T: 'static, E: 'static> AppCreator<T, E> {
+    /// same as [AppCreator::new] but the event loop can receive events of the type `E`
+    ///
+    /// send them from any thread with the [EventLoopProxy] (see [AppCreator::event_loop_proxy] | [AppData::event_loop_proxy]),
+    /// they get handled on the main thread by [AppCreator::user_event]
+    ///
+    /// ```no_run
+    /// # use wgpu_noboiler::app::AppCreator;
+    /// let app_creator = AppCreator::<(), String>::with_user_events(());
+    /// ```
+    pub fn with_user_events(state: T) -> AppCreator<T, E> {
+        Self::try_with_user_events(state).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// same as [AppCreator::with_user_events] but returns an [NoboilerError] if the window could not be created
+    pub fn try_with_user_events(state: T) -> Result<AppCreator<T, E>, NoboilerError> {
+        let event_loop = EventLoopBuilder::with_user_event().build();
         let window = WindowBuilder::new()
             .with_visible(false)
             .build(&event_loop)
-            .unwrap();
+            .map_err(NoboilerError::WindowCreation)?;
+
+        Ok(Self::with_target(
+            state,
+            TargetCreator::Window { window, event_loop },
+        ))
+    }
 
+    fn with_target(state: T, target: TargetCreator<E>) -> AppCreator<T, E> {
         AppCreator {
             state,
 
-            window,
-            event_loop,
+            target,
             window_event_fn: None,
             resize_fn: None,
             update_fn: None,
+            fixed_update_fn: None,
             render_fn: None,
             init_fn: None,
+            user_event_fn: None,
 
             present_mode: PresentMode::Fifo,
             power_preference: PowerPreference::LowPower,
             device_limits: Limits::default(),
+            force_fallback_adapter: false,
+            watch_shaders: false,
+            capture_frames: false,
+            fixed_delta_time: 1.0 / 60.0,
+            max_fixed_steps: 5,
+            frame_stats_capacity: 300,
+            target_fps: None,
+            windows: vec![],
+            depth_format: None,
+            sample_count: 1,
 
             view_formats: vec![],
         }
@@ -215,41 +977,102 @@ impl<T: 'static> AppCreator<T> {
     /// gets called on every [WindowEvent]
     ///
     /// e.g. mouse | keyboard input
-    pub fn window_event(mut self, input: WindowEventFn<T>) -> Self {
-        self.window_event_fn = Some(input);
+    pub fn window_event(
+        mut self,
+        input: impl FnMut(&AppData, &mut T, &WindowEvent) + 'static,
+    ) -> Self {
+        self.window_event_fn = Some(Box::new(input));
         self
     }
 
     /// gets called on every ResizeEvent
-    pub fn resize(mut self, resize: ResizeFn<T>) -> Self {
-        self.resize_fn = Some(resize);
+    pub fn resize(mut self, resize: impl FnMut(&AppData, &mut T, (u32, u32)) + 'static) -> Self {
+        self.resize_fn = Some(Box::new(resize));
         self
     }
 
     ///gets called on every frame just before [AppCreator::render]
     ///
     /// here you can change your State
-    pub fn update(mut self, update: UpdateFn<T>) -> Self {
-        self.update_fn = Some(update);
+    pub fn update(mut self, update: impl FnMut(&AppData, &mut T) + 'static) -> Self {
+        self.update_fn = Some(Box::new(update));
+        self
+    }
+
+    /// gets called `hz` times per second with a constant [AppData::fixed_delta_time], before [AppCreator::update]
+    ///
+    /// depending on the frame rate it runs zero or more times per frame (at most [AppCreator::max_fixed_steps]).
+    /// use it for frame-rate independent logic like physics and [AppData::interpolation_alpha] to render smoothly between the steps
+    ///
+    /// # Panics
+    ///
+    /// if `hz` is not a finite number greater than 0
+    pub fn fixed_update(
+        mut self,
+        hz: f64,
+        fixed_update: impl FnMut(&AppData, &mut T) + 'static,
+    ) -> Self {
+        assert!(
+            hz.is_finite() && hz > 0.0,
+            "the rate of fixed_update has to be a finite number greater than 0 but got {}",
+            hz
+        );
+
+        self.fixed_delta_time = 1.0 / hz;
+        self.fixed_update_fn = Some(Box::new(fixed_update));
+        self
+    }
+
+    /// sets how many [fixed updates](AppCreator::fixed_update) can run in one frame to catch up
+    ///
+    /// if more would be needed the missing time gets skipped, so slow updates can not stall the [App]
+    ///
+    /// default: 5
+    pub fn max_fixed_steps(mut self, max_fixed_steps: u32) -> Self {
+        self.max_fixed_steps = max_fixed_steps;
         self
     }
 
     ///gets called on every frame just after [AppCreator::update]
     ///
     /// here you can render your frame
-    pub fn render(mut self, render: RenderFn<T>) -> Self {
-        self.render_fn = Some(render);
+    pub fn render(
+        mut self,
+        render: impl FnMut(&AppData, &mut T, CommandEncoder, TextureView) + 'static,
+    ) -> Self {
+        self.render_fn = Some(Box::new(render));
         self
     }
 
     /// gets called before opening the window
     ///
-    /// mainly used to create your [RenderPipelines](RenderPipeline)
-    pub fn init(mut self, init: InitFn<T>) -> Self {
-        self.init_fn = Some(init);
+    /// mainly used to create your [RenderPipelines](wgpu::RenderPipeline)
+    pub fn init(
+        mut self,
+        init: impl FnMut(&AppData, &mut T, &mut PipelineRegistry) + 'static,
+    ) -> Self {
+        self.init_fn = Some(Box::new(init));
+        self
+    }
+
+    /// gets called on the main thread for every event sent with the [EventLoopProxy]
+    ///
+    /// see [AppCreator::with_user_events]
+    pub fn user_event(mut self, user_event: impl FnMut(&AppData, &mut T, E) + 'static) -> Self {
+        self.user_event_fn = Some(Box::new(user_event));
         self
     }
 
+    /// returns an [EventLoopProxy] which sends events to [AppCreator::user_event] (also from other threads)
+    ///
+    /// [None] when created with [AppCreator::headless]
+    pub fn event_loop_proxy(&self) -> Option<EventLoopProxy<E>> {
+        match &self.target {
+            TargetCreator::Window { event_loop, .. } => Some(event_loop.create_proxy()),
+            TargetCreator::Headless { .. } => None,
+        }
+    }
+
     /// sets the [PresentMode] of the [Surface]
     ///
     /// default: [PresentMode::Fifo]
@@ -258,18 +1081,66 @@ impl<T: 'static> AppCreator<T> {
         self
     }
 
-    pub fn title(self, title: &str) -> Self {
-        self.window.set_title(title);
+    pub fn title(mut self, title: &str) -> Self {
+        if let Some(window) = self.try_get_window() {
+            window.set_title(title);
+        }
         self
     }
 
-    pub fn resizable(self, resizable: bool) -> Self {
-        self.window.set_resizable(resizable);
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        if let Some(window) = self.try_get_window() {
+            window.set_resizable(resizable);
+        }
         self
     }
 
+    /// opens an additional window when the [App] starts, see [AppData::open_window]
+    ///
+    /// does nothing in [headless](AppCreator::headless) mode
+    pub fn add_window(mut self, name: &str, window_builder: WindowBuilder) -> Self {
+        self.windows.push((name.to_owned(), window_builder));
+        self
+    }
+
+    /// returns the [Window]
+    ///
+    /// # Panics
+    ///
+    /// when created with [AppCreator::headless], use [AppCreator::try_get_window] instead
     pub fn get_window(&mut self) -> &mut Window {
-        &mut self.window
+        self.try_get_window()
+            .expect("get_window is not available in headless mode")
+    }
+
+    /// returns the [Window]
+    ///
+    /// [None] when created with [AppCreator::headless]
+    pub fn try_get_window(&mut self) -> Option<&mut Window> {
+        match &mut self.target {
+            TargetCreator::Window { window, .. } => Some(window),
+            TargetCreator::Headless { .. } => None,
+        }
+    }
+
+    /// sets the number of frames which get rendered in [headless](AppCreator::headless) mode
+    ///
+    /// default: 1
+    pub fn frames(mut self, count: u32) -> Self {
+        if let TargetCreator::Headless { frames, .. } = &mut self.target {
+            *frames = count;
+        }
+        self
+    }
+
+    /// sets the [AppData::delta_time] used for every frame in [headless](AppCreator::headless) mode
+    ///
+    /// default: 1/60 seconds
+    pub fn headless_delta_time(mut self, seconds: f64) -> Self {
+        if let TargetCreator::Headless { delta_time, .. } = &mut self.target {
+            *delta_time = seconds;
+        }
+        self
     }
 
     pub fn add_view_formats(mut self, texture_format: TextureFormat) -> Self {
@@ -279,7 +1150,7 @@ impl<T: 'static> AppCreator<T> {
 
     /// sets the [PowerPreference] of the [Adapter]
     ///
-    /// default: [PresentMode::Fifo]
+    /// default: [PowerPreference::LowPower]
     pub fn power_preference(mut self, power_preference: PowerPreference) -> Self {
         self.power_preference = power_preference;
         self
@@ -293,34 +1164,168 @@ impl<T: 'static> AppCreator<T> {
         self
     }
 
-    fn create_app_data(&self) -> AppData {
-        env_logger::init();
-        let size = self.window.inner_size();
+    /// forces wgpu to use its fallback (software) [Adapter]
+    ///
+    /// in [headless](AppCreator::headless) mode the fallback [Adapter] is also used when no other one is found
+    ///
+    /// default: false
+    pub fn force_fallback_adapter(mut self, force_fallback_adapter: bool) -> Self {
+        self.force_fallback_adapter = force_fallback_adapter;
+        self
+    }
+
+    /// rebuilds pipelines created with [RenderPipelineCreator::build_watched](crate::render_pipeline::RenderPipelineCreator::build_watched) when their shader file changes
+    ///
+    /// the new pipeline replaces the one with the same name in [AppData::render_pipelines] between frames.
+    /// if the changed shader does not compile the old pipeline is kept and the error gets logged
+    ///
+    /// default: false
+    pub fn watch_shaders(mut self, watch_shaders: bool) -> Self {
+        self.watch_shaders = watch_shaders;
+        self
+    }
+
+    /// makes [AppData::capture_frame] available with a window
+    ///
+    /// the surface can not be copied from on most backends, so the main window renders into a texture which gets copied onto the surface (one additional fullscreen draw per frame).
+    /// has no effect in [headless](AppCreator::headless) mode, where frames can always be captured
+    ///
+    /// default: false
+    pub fn capture_frames(mut self, capture_frames: bool) -> Self {
+        self.capture_frames = capture_frames;
+        self
+    }
+
+    /// limits how many frames get rendered per second, independent of the [PresentMode]
+    ///
+    /// the event loop sleeps until shortly before the next frame and spins the rest, so the frame rate is accurate while the cpu is mostly idle.
+    /// can be changed while running with [AppData::set_target_fps], has no effect in [headless](AppCreator::headless) mode
+    ///
+    /// default: [None] (not limited)
+    pub fn target_fps(mut self, target_fps: Option<f64>) -> Self {
+        self.target_fps = target_fps;
+        self
+    }
+
+    /// sets over how many frames the [AppData::frame_stats] (and [AppData::fps]) are calculated
+    ///
+    /// default: 300
+    ///
+    /// # Panics
+    ///
+    /// if `capacity` is 0
+    pub fn frame_stats_capacity(mut self, capacity: usize) -> Self {
+        assert!(capacity > 0, "the capacity of FrameStats has to be greater than 0");
+
+        self.frame_stats_capacity = capacity;
+        self
+    }
+
+    /// creates a depth buffer with the [TextureFormat] (e.g. [TextureFormat::Depth32Float]) which gets resized with the [Surface]
+    ///
+    /// use [RenderPassCreator::with_depth](crate::render_pass::RenderPassCreator::with_depth) (or [RenderPassCreator::from_app](crate::render_pass::RenderPassCreator::from_app)) to use it,
+    /// pipelines created from the [AppData] test against it by default
+    ///
+    /// default: no depth buffer
+    pub fn depth_buffer(mut self, format: TextureFormat) -> Self {
+        self.depth_format = Some(format);
+        self
+    }
+
+    /// sets the number of samples per pixel used for multisample anti-aliasing (MSAA)
+    ///
+    /// a multisampled color texture gets created which is resized with the [Surface].
+    /// use [RenderPassCreator::with_msaa](crate::render_pass::RenderPassCreator::with_msaa) to resolve it into the frame,
+    /// pipelines created with the [AppData] as target use the same sample count.
+    /// the count is validated against the [Adapter] when the [App] gets created, 4 is always supported
+    ///
+    /// **with a sample count above 1, render passes created with only [RenderPassCreator::new](crate::render_pass::RenderPassCreator::new) fail validation**
+    /// (the pipelines draw multisampled but the frame is single sampled),
+    /// create them with [RenderPassCreator::from_app](crate::render_pass::RenderPassCreator::from_app) instead
+    ///
+    /// default: 1 (no MSAA)
+    pub fn sample_count(mut self, sample_count: u32) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+
+    fn try_create_app_data(&self) -> Result<AppData, NoboilerError> {
+        let _ = env_logger::try_init();
 
         let instance = Instance::default();
-        let surface = unsafe { instance.create_surface(&self.window).unwrap() };
 
-        let adapter: Adapter =
+        let (size, surface) = match &self.target {
+            TargetCreator::Window { window, .. } => (
+                window.inner_size(),
+                Some(
+                    unsafe { instance.create_surface(window) }
+                        .map_err(NoboilerError::SurfaceCreation)?,
+                ),
+            ),
+            TargetCreator::Headless { size, .. } => (*size, None),
+        };
+
+        let request_adapter = |force_fallback_adapter| {
             pollster::block_on(instance.request_adapter(&RequestAdapterOptions {
                 power_preference: self.power_preference,
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
+                compatible_surface: surface.as_ref(),
+                force_fallback_adapter,
             }))
-                .unwrap();
+        };
+
+        let adapter: Adapter = request_adapter(self.force_fallback_adapter)
+            .or_else(|| {
+                if surface.is_none() && !self.force_fallback_adapter {
+                    request_adapter(true)
+                } else {
+                    None
+                }
+            })
+            .ok_or(NoboilerError::AdapterNotFound)?;
+
+        let format = match &surface {
+            Some(surface) => *surface
+                .get_capabilities(&adapter)
+                .formats
+                .first()
+                .ok_or(NoboilerError::SurfaceUnsupported)?,
+            None => TextureFormat::Rgba8UnormSrgb,
+        };
+
+        let mut features = Features::empty();
+
+        if let Some(depth_format) = self.depth_format {
+            let required_features = depth_format.describe().required_features;
+
+            if !is_depth_format(depth_format) || !adapter.features().contains(required_features) {
+                return Err(NoboilerError::DepthFormatUnsupported(depth_format));
+            }
+
+            features |= required_features;
+        }
+
+        if self.sample_count != 1 {
+            // the color texture gets resolved into the frame, the depth texture does not
+            features |= msaa_features(&adapter, format, true, self.sample_count)?;
+
+            if let Some(depth_format) = self.depth_format {
+                features |= msaa_features(&adapter, depth_format, false, self.sample_count)?;
+            }
+        }
 
         let (device, queue) = pollster::block_on(adapter.request_device(
             &DeviceDescriptor {
-                features: wgpu::Features::empty(),
+                features,
                 limits: self.device_limits.clone(),
                 label: None,
             },
             None,
         ))
-            .unwrap();
+        .map_err(NoboilerError::DeviceRequest)?;
 
         let config = SurfaceConfiguration {
             usage: TextureUsages::RENDER_ATTACHMENT,
-            format: surface.get_capabilities(&adapter).formats[0],
+            format,
             width: size.width,
             height: size.height,
             present_mode: self.present_mode,
@@ -328,51 +1333,247 @@ impl<T: 'static> AppCreator<T> {
             view_formats: self.view_formats.clone(),
         };
 
-        surface.configure(&device, &config);
+        let target = match surface {
+            Some(surface) => {
+                surface.configure(&device, &config);
+                RenderTarget::Surface(surface)
+            }
+            None => RenderTarget::Texture(create_offscreen_texture(&device, &config)),
+        };
 
-        AppData {
-            surface,
+        let frame_blit = (self.capture_frames && matches!(target, RenderTarget::Surface(_)))
+            .then(|| FrameBlit::new(&device, &config));
+
+        let depth_buffer = self
+            .depth_format
+            .map(|format| DepthBuffer::new(&device, &config, format, self.sample_count));
+
+        let msaa_buffer = (self.sample_count != 1)
+            .then(|| MsaaBuffer::new(&device, &config, self.sample_count));
+
+        let upload_allocator = UploadAllocator::new(&device);
+
+        Ok(AppData {
+            target,
+            frame_blit,
+            event_loop_proxy: self
+                .event_loop_proxy()
+                .map(|event_loop_proxy| Box::new(event_loop_proxy) as Box<dyn Any>),
+            window: None,
+            window_name: MAIN_WINDOW_NAME.to_owned(),
+            windows: vec![],
+            pending_windows: RefCell::new(self.windows.clone()),
+            instance,
+            adapter,
+            depth_buffer,
+            sample_count: self.sample_count,
+            msaa_buffer,
             device,
             queue,
             config,
             size,
 
             last_frame_instant: Instant::now(),
-            render_instant: Instant::now(),
-
-            render_pipelines: Vec::new(),
+            frame_stats: FrameStats::new(self.frame_stats_capacity),
+
+            render_pipelines: PipelineRegistry::new(),
+            input: InputState::default(),
+            upload_allocator: RefCell::new(upload_allocator),
+            target_fps: Cell::new(self.target_fps),
+            watch_shaders: self.watch_shaders,
+            shader_watcher: RefCell::new(ShaderWatcher::new()),
             fps: 0.0,
 
             delta_time: 1.0,
             render_time: 1.0,
             update_time: 1.0,
-        }
+
+            fixed_delta_time: self.fixed_delta_time,
+            interpolation_alpha: 0.0,
+            fixed_update_accumulator: 0.0,
+            max_fixed_steps: self.max_fixed_steps,
+        })
     }
 
-    /// opens the window and starts the [AppCreator::update] | [AppCreator::render] loop
-    pub fn run(self) {
+    fn into_app(self, app_data: AppData) -> (App<T, E>, TargetCreator<E>) {
         let app = App {
-            app_data: self.create_app_data(),
+            app_data,
 
             state: self.state,
 
             window_event_fn: self.window_event_fn,
             resize_fn: self.resize_fn,
             update_fn: self.update_fn,
+            fixed_update_fn: self.fixed_update_fn,
             render_fn: self.render_fn,
             init_fn: self.init_fn,
+            user_event_fn: self.user_event_fn,
         };
 
-        app.run(self.window, self.event_loop);
+        (app, self.target)
+    }
+
+    /// opens the window and starts the [AppCreator::update] | [AppCreator::render] loop
+    ///
+    /// in [headless](AppCreator::headless) mode the configured amount of [frames](AppCreator::frames) get rendered and the function returns
+    ///
+    /// # Panics
+    ///
+    /// if the setup fails, use [AppCreator::try_run] to handle the error
+    pub fn run(self) {
+        if let Err(err) = self.try_run() {
+            panic!("{}", err);
+        }
+    }
+
+    /// same as [AppCreator::run] but returns a [RunError] if the setup fails
+    ///
+    /// the [RunError] contains this [AppCreator] so it can be adjusted and run again
+    pub fn try_run(self) -> Result<(), RunError<T, E>> {
+        let app_data = match self.try_create_app_data() {
+            Ok(app_data) => app_data,
+            Err(error) => {
+                return Err(RunError {
+                    error,
+                    app_creator: Box::new(self),
+                })
+            }
+        };
+
+        let (app, target) = self.into_app(app_data);
+
+        match target {
+            TargetCreator::Window { window, event_loop } => app.run(window, event_loop),
+            TargetCreator::Headless {
+                frames, delta_time, ..
+            } => {
+                app.run_headless(frames, delta_time);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// renders the configured amount of [frames](AppCreator::frames) and returns the resulting [AppData] and state
+    ///
+    /// # Panics
+    ///
+    /// if the [AppCreator] was not created with [AppCreator::headless] or the setup fails
+    pub fn run_headless(self) -> (AppData, T) {
+        self.try_run_headless()
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// same as [AppCreator::run_headless] but returns a [RunError] if the setup fails
+    ///
+    /// # Panics
+    ///
+    /// if the [AppCreator] was not created with [AppCreator::headless]
+    pub fn try_run_headless(self) -> Result<(AppData, T), RunError<T, E>> {
+        if !matches!(self.target, TargetCreator::Headless { .. }) {
+            panic!("run_headless requires an AppCreator created with AppCreator::headless")
+        }
+
+        let app_data = match self.try_create_app_data() {
+            Ok(app_data) => app_data,
+            Err(error) => {
+                return Err(RunError {
+                    error,
+                    app_creator: Box::new(self),
+                })
+            }
+        };
+
+        let (app, target) = self.into_app(app_data);
+
+        let TargetCreator::Headless {
+            frames, delta_time, ..
+        } = target
+        else {
+            unreachable!()
+        };
+
+        Ok(app.run_headless(frames, delta_time))
     }
 }
 
+/// the [Features] which are needed to use `format` with the sample count
+fn msaa_features(
+    adapter: &Adapter,
+    format: TextureFormat,
+    resolve: bool,
+    sample_count: u32,
+) -> Result<Features, NoboilerError> {
+    let supported = adapter.get_texture_format_features(format).flags;
+
+    if !supported.sample_count_supported(sample_count)
+        || (resolve && !supported.contains(TextureFormatFeatureFlags::MULTISAMPLE_RESOLVE))
+    {
+        return Err(NoboilerError::SampleCountUnsupported(sample_count));
+    }
+
+    let guaranteed = format.describe().guaranteed_format_features.flags;
+
+    if guaranteed.sample_count_supported(sample_count) {
+        Ok(Features::empty())
+    } else if adapter
+        .features()
+        .contains(Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+    {
+        Ok(Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+    } else {
+        Err(NoboilerError::SampleCountUnsupported(sample_count))
+    }
+}
+
+fn is_depth_format(format: TextureFormat) -> bool {
+    matches!(
+        format,
+        TextureFormat::Depth16Unorm
+            | TextureFormat::Depth24Plus
+            | TextureFormat::Depth24PlusStencil8
+            | TextureFormat::Depth32Float
+            | TextureFormat::Depth32FloatStencil8
+    )
+}
+
+/// callback for [AppCreator::window_event]
+///
+/// can be a plain `fn` or a closure which captures its environment
+pub type BoxedWindowEventFn<T> = Box<dyn FnMut(&AppData, &mut T, &WindowEvent)>;
+
+/// callback for [AppCreator::resize]
+pub type BoxedResizeFn<T> = Box<dyn FnMut(&AppData, &mut T, (u32, u32))>;
+
+/// callback for [AppCreator::update]
+pub type BoxedUpdateFn<T> = Box<dyn FnMut(&AppData, &mut T)>;
+
+/// callback for [AppCreator::fixed_update]
+pub type BoxedFixedUpdateFn<T> = Box<dyn FnMut(&AppData, &mut T)>;
+
+/// callback for [AppCreator::render]
+pub type BoxedRenderFn<T> = Box<dyn FnMut(&AppData, &mut T, CommandEncoder, TextureView)>;
+
+/// callback for [AppCreator::user_event]
+pub type BoxedUserEventFn<T, E> = Box<dyn FnMut(&AppData, &mut T, E)>;
+
+/// callback for [AppCreator::init]
+pub type BoxedInitFn<T> = Box<dyn FnMut(&AppData, &mut T, &mut PipelineRegistry)>;
+
+/// `fn` pointer version of [BoxedWindowEventFn]
+#[deprecated(note = "callbacks are stored as BoxedWindowEventFn, use a plain fn or closure")]
 pub type WindowEventFn<T> = fn(app_data: &AppData, state: &mut T, window_event: &WindowEvent);
 
+/// `fn` pointer version of [BoxedResizeFn]
+#[deprecated(note = "callbacks are stored as BoxedResizeFn, use a plain fn or closure")]
 pub type ResizeFn<T> = fn(app_data: &AppData, state: &mut T, size: (u32, u32));
 
+/// `fn` pointer version of [BoxedUpdateFn]
+#[deprecated(note = "callbacks are stored as BoxedUpdateFn, use a plain fn or closure")]
 pub type UpdateFn<T> = fn(app_data: &AppData, state: &mut T);
 
+/// `fn` pointer version of [BoxedRenderFn]
+#[deprecated(note = "callbacks are stored as BoxedRenderFn, use a plain fn or closure")]
 pub type RenderFn<T> = fn(
     app_data: &AppData,
     state: &mut T,
@@ -380,5 +1581,7 @@ pub type RenderFn<T> = fn(
     texture_view: TextureView,
 );
 
+/// `fn` pointer version of [BoxedInitFn]
+#[deprecated(note = "callbacks are stored as BoxedInitFn, use a plain fn or closure")]
 pub type InitFn<T> =
-fn(app_data: &AppData, state: &mut T, render_pipelines: &mut Vec<RenderPipeline>);
+    fn(app_data: &AppData, state: &mut T, render_pipelines: &mut PipelineRegistry);
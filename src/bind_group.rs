@@ -0,0 +1,263 @@
+use bytemuck::Pod;
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingResource, BindingType, Buffer, BufferBindingType, Device,
+    ErrorFilter, Sampler, SamplerBindingType, ShaderStages, TextureSampleType, TextureView,
+    TextureViewDimension,
+};
+
+use crate::buffer::UniformBuffer;
+use crate::error::NoboilerError;
+
+/// Builder Patter for wgpu [BindGroupLayout]
+///
+/// the bindings are numbered in the order the entries get added
+pub struct BindGroupLayoutCreator<'a> {
+    device: &'a Device,
+
+    entries: Vec<BindGroupLayoutEntry>,
+
+    label: &'a str,
+}
+
+impl<'a> BindGroupLayoutCreator<'a> {
+    pub fn new(device: &'a Device) -> BindGroupLayoutCreator<'a> {
+        BindGroupLayoutCreator {
+            device,
+            entries: vec![],
+            label: "Bind Group Layout",
+        }
+    }
+
+    /// sets label (name)
+    pub fn label(mut self, label: &'a str) -> Self {
+        self.label = label;
+        self
+    }
+
+    /// adds an uniform-buffer entry
+    pub fn add_uniform(self, visibility: ShaderStages) -> Self {
+        self.add_entry(
+            visibility,
+            BindingType::Buffer {
+                ty: BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+        )
+    }
+
+    /// adds a storage-buffer entry
+    pub fn add_storage(self, visibility: ShaderStages, read_only: bool) -> Self {
+        self.add_entry(
+            visibility,
+            BindingType::Buffer {
+                ty: BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+        )
+    }
+
+    /// adds a filterable 2D float-texture entry
+    pub fn add_texture(self, visibility: ShaderStages) -> Self {
+        self.add_entry(
+            visibility,
+            BindingType::Texture {
+                sample_type: TextureSampleType::Float { filterable: true },
+                view_dimension: TextureViewDimension::D2,
+                multisampled: false,
+            },
+        )
+    }
+
+    /// adds a filtering sampler entry
+    pub fn add_sampler(self, visibility: ShaderStages) -> Self {
+        self.add_entry(
+            visibility,
+            BindingType::Sampler(SamplerBindingType::Filtering),
+        )
+    }
+
+    /// adds an entry with any [BindingType]
+    pub fn add_entry(mut self, visibility: ShaderStages, ty: BindingType) -> Self {
+        self.entries.push(BindGroupLayoutEntry {
+            binding: self.entries.len() as u32,
+            visibility,
+            ty,
+            count: None,
+        });
+
+        self
+    }
+
+    /// creates a [SimpleBindGroupLayout]
+    pub fn build(&self) -> SimpleBindGroupLayout {
+        SimpleBindGroupLayout {
+            layout: self
+                .device
+                .create_bind_group_layout(&BindGroupLayoutDescriptor {
+                    label: Some(self.label),
+                    entries: &self.entries,
+                }),
+            entries: self.entries.clone(),
+        }
+    }
+}
+
+/// wrapper for [BindGroupLayout] which stores its entries
+pub struct SimpleBindGroupLayout {
+    layout: BindGroupLayout,
+    entries: Vec<BindGroupLayoutEntry>,
+}
+
+impl SimpleBindGroupLayout {
+    pub fn layout(&self) -> &BindGroupLayout {
+        &self.layout
+    }
+
+    pub fn entries(&self) -> &[BindGroupLayoutEntry] {
+        &self.entries
+    }
+}
+
+/// Builder Patter for wgpu [BindGroup]
+///
+/// the resources get bound in the order they are added and are checked against the entries of the [SimpleBindGroupLayout]
+pub struct BindGroupCreator<'a> {
+    device: &'a Device,
+    layout: &'a SimpleBindGroupLayout,
+
+    resources: Vec<BindingResource<'a>>,
+
+    label: &'a str,
+}
+
+impl<'a> BindGroupCreator<'a> {
+    pub fn new(device: &'a Device, layout: &'a SimpleBindGroupLayout) -> BindGroupCreator<'a> {
+        BindGroupCreator {
+            device,
+            layout,
+            resources: vec![],
+            label: "Bind Group",
+        }
+    }
+
+    /// sets label (name)
+    pub fn label(mut self, label: &'a str) -> Self {
+        self.label = label;
+        self
+    }
+
+    /// binds the whole [Buffer] (uniform | storage)
+    pub fn add_buffer(self, buffer: &'a Buffer) -> Self {
+        self.add_resource(buffer.as_entire_binding())
+    }
+
+    /// binds an [UniformBuffer]
+    pub fn add_uniform<T: Pod>(self, uniform_buffer: &'a UniformBuffer<T>) -> Self {
+        self.add_resource(uniform_buffer.binding_resource())
+    }
+
+    /// binds a [TextureView]
+    pub fn add_texture_view(self, texture_view: &'a TextureView) -> Self {
+        self.add_resource(BindingResource::TextureView(texture_view))
+    }
+
+    /// binds a [Sampler]
+    pub fn add_sampler(self, sampler: &'a Sampler) -> Self {
+        self.add_resource(BindingResource::Sampler(sampler))
+    }
+
+    /// binds any [BindingResource]
+    pub fn add_resource(mut self, resource: BindingResource<'a>) -> Self {
+        self.resources.push(resource);
+        self
+    }
+
+    /// creates a [BindGroup]
+    ///
+    /// # Panics
+    ///
+    /// if the resources do not match the layout, use [BindGroupCreator::try_build] to handle the error
+    pub fn build(&self) -> BindGroup {
+        self.try_build().unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// same as [BindGroupCreator::build] but returns an [NoboilerError] if the resources do not match the layout
+    pub fn try_build(&self) -> Result<BindGroup, NoboilerError> {
+        let layout_entries = self.layout.entries();
+
+        if layout_entries.len() != self.resources.len() {
+            return Err(NoboilerError::BindGroupMismatch {
+                binding: self.resources.len().min(layout_entries.len()) as u32,
+                message: format!(
+                    "the layout has {} entries but {} resources were added",
+                    layout_entries.len(),
+                    self.resources.len()
+                ),
+            });
+        }
+
+        for (entry, resource) in layout_entries.iter().zip(&self.resources) {
+            let matches = matches!(
+                (&entry.ty, resource),
+                (
+                    BindingType::Buffer { .. },
+                    BindingResource::Buffer(_) | BindingResource::BufferArray(_)
+                ) | (
+                    BindingType::Sampler(_),
+                    BindingResource::Sampler(_) | BindingResource::SamplerArray(_)
+                ) | (
+                    BindingType::Texture { .. } | BindingType::StorageTexture { .. },
+                    BindingResource::TextureView(_) | BindingResource::TextureViewArray(_)
+                )
+            );
+
+            if !matches {
+                return Err(NoboilerError::BindGroupMismatch {
+                    binding: entry.binding,
+                    message: format!(
+                        "expected a resource for {:?} but got {}",
+                        entry.ty,
+                        resource_name(resource)
+                    ),
+                });
+            }
+        }
+
+        let entries: Vec<BindGroupEntry> = self
+            .resources
+            .iter()
+            .zip(layout_entries)
+            .map(|(resource, entry)| BindGroupEntry {
+                binding: entry.binding,
+                resource: resource.clone(),
+            })
+            .collect();
+
+        self.device.push_error_scope(ErrorFilter::Validation);
+
+        let bind_group = self.device.create_bind_group(&BindGroupDescriptor {
+            label: Some(self.label),
+            layout: self.layout.layout(),
+            entries: &entries,
+        });
+
+        match pollster::block_on(self.device.pop_error_scope()) {
+            None => Ok(bind_group),
+            Some(err) => Err(NoboilerError::BindGroup(err)),
+        }
+    }
+}
+
+fn resource_name(resource: &BindingResource) -> &'static str {
+    match resource {
+        BindingResource::Buffer(_) | BindingResource::BufferArray(_) => "a buffer",
+        BindingResource::Sampler(_) | BindingResource::SamplerArray(_) => "a sampler",
+        BindingResource::TextureView(_) | BindingResource::TextureViewArray(_) => {
+            "a texture view"
+        }
+        _ => "an unknown resource",
+    }
+}
@@ -1,28 +1,62 @@
+use std::marker::PhantomData;
+use std::mem;
+
 use bytemuck::Pod;
 use wgpu::util::{BufferInitDescriptor, DeviceExt};
-use wgpu::{Buffer, BufferSlice, BufferUsages, Device};
+use wgpu::{
+    BindGroupEntry, BindingResource, Buffer, BufferAddress, BufferDescriptor, BufferSlice,
+    BufferUsages, Device, IndexFormat, Queue, COPY_BUFFER_ALIGNMENT,
+};
 
 /// Builder Patter for wgpu [Buffer]
 pub struct BufferCreator<'a, T: Pod> {
     data: Vec<T>,
     device: &'a Device,
     usage: BufferUsages,
+    index_format: Option<IndexFormat>,
+    compact: bool,
 
     label: &'a str,
 }
 
-impl<'a> BufferCreator<'a, i32> {
+/// type of the elements of an indices-buffer (`u16` | `u32`)
+pub trait IndexType: Pod {
+    const FORMAT: IndexFormat;
+}
+
+impl IndexType for u16 {
+    const FORMAT: IndexFormat = IndexFormat::Uint16;
+}
+
+impl IndexType for u32 {
+    const FORMAT: IndexFormat = IndexFormat::Uint32;
+}
+
+impl<'a, I: IndexType> BufferCreator<'a, I> {
     /// Helps to create indices-buffer
     ///
-    /// IndexFormat: u32
-    pub fn indices(device: &'a Device) -> BufferCreator<'a, i32> {
+    /// IndexFormat: u16 | u32 depending on `I`
+    pub fn indices(device: &'a Device) -> BufferCreator<'a, I> {
         BufferCreator {
             data: vec![],
             device,
             usage: BufferUsages::INDEX,
+            index_format: Some(I::FORMAT),
+            compact: false,
             label: "Indices Buffer",
         }
     }
+
+    /// sets if [BufferCreator::build] stores u32 indices as u16 when all of them fit into it
+    ///
+    /// the buffer then has to be used with its [index_format](SimpleBuffer::index_format) (e.g. with [RenderPassExt::set_indices](crate::render_pass::RenderPassExt::set_indices)),
+    /// [BufferCreator::build_dynamic] never compacts because later data might not fit
+    ///
+    /// default: false
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
 }
 
 impl<'a, T: Pod> BufferCreator<'a, T> {
@@ -32,10 +66,26 @@ impl<'a, T: Pod> BufferCreator<'a, T> {
             data: vec![],
             device,
             usage: BufferUsages::VERTEX,
+            index_format: None,
+            compact: false,
             label: "Vertex Buffer",
         }
     }
 
+    /// Helps to create instance-buffer
+    ///
+    /// the layout has to be added with [RenderPipelineCreator::add_instance_buffer](crate::render_pipeline::RenderPipelineCreator::add_instance_buffer)
+    pub fn instance(device: &'a Device) -> BufferCreator<'a, T> {
+        BufferCreator {
+            data: vec![],
+            device,
+            usage: BufferUsages::VERTEX,
+            index_format: None,
+            compact: false,
+            label: "Instance Buffer",
+        }
+    }
+
     /// sets Label (name of the buffer)
     pub fn label(mut self, label: &'a str) -> Self {
         self.label = label;
@@ -67,14 +117,60 @@ impl<'a, T: Pod> BufferCreator<'a, T> {
 
     /// creates SimpleBuffer
     pub fn build(&self) -> SimpleBuffer {
+        let compacted;
+        let mut index_format = self.index_format;
+        let mut contents: &[u8] = bytemuck::cast_slice(&self.data);
+
+        if self.compact && index_format == Some(IndexFormat::Uint32) {
+            // only indices() sets the format, so the data are u32
+            let indices: &[u32] = bytemuck::cast_slice(&self.data);
+
+            if indices.iter().all(|&index| index <= u16::MAX as u32) {
+                compacted = indices.iter().map(|&index| index as u16).collect::<Vec<u16>>();
+                contents = bytemuck::cast_slice(&compacted);
+                index_format = Some(IndexFormat::Uint16);
+            }
+        }
+
         SimpleBuffer {
             buffer: self.device.create_buffer_init(&BufferInitDescriptor {
                 label: Some(self.label),
-                contents: bytemuck::cast_slice(&self.data),
+                contents,
                 usage: self.usage,
             }),
 
             size: self.data.len() as u32,
+            index_format,
+        }
+    }
+
+    /// creates [DynamicBuffer] which can be updated with [DynamicBuffer::write] | [DynamicBuffer::replace]
+    ///
+    /// indices are never [compacted](BufferCreator::compact) to u16 because later data might not fit
+    pub fn build_dynamic(&self) -> DynamicBuffer<T> {
+        let usage = self.usage | BufferUsages::COPY_DST;
+
+        let mut contents = bytemuck::cast_slice(&self.data).to_vec();
+        contents.resize(
+            align(contents.len() as BufferAddress, COPY_BUFFER_ALIGNMENT) as usize,
+            0,
+        );
+
+        DynamicBuffer {
+            buffer: self.device.create_buffer_init(&BufferInitDescriptor {
+                label: Some(self.label),
+                contents: &contents,
+                usage,
+            }),
+            contents,
+            usage,
+            index_format: self.index_format,
+            label: self.label.to_owned(),
+
+            size: self.data.len() as u32,
+            capacity: self.data.len() as u32,
+
+            phantom: PhantomData,
         }
     }
 }
@@ -83,6 +179,7 @@ impl<'a, T: Pod> BufferCreator<'a, T> {
 pub struct SimpleBuffer {
     buffer: Buffer,
     size: u32,
+    index_format: Option<IndexFormat>,
 }
 
 impl SimpleBuffer {
@@ -90,7 +187,242 @@ impl SimpleBuffer {
         self.size
     }
 
-    pub fn slice(&self) -> BufferSlice {
+    pub fn slice(&self) -> BufferSlice<'_> {
         self.buffer.slice(..)
     }
+
+    /// the [IndexFormat] of an indices-buffer, [None] for other buffers
+    pub fn index_format(&self) -> Option<IndexFormat> {
+        self.index_format
+    }
+}
+
+/// [Buffer] which knows the count of its elements, see [RenderPassExt](crate::render_pass::RenderPassExt)
+pub trait ElementBuffer {
+    /// count of elements
+    fn size(&self) -> u32;
+
+    /// [BufferSlice] of the elements
+    fn slice(&self) -> BufferSlice<'_>;
+
+    /// the [IndexFormat] of an indices-buffer, [None] for other buffers
+    fn index_format(&self) -> Option<IndexFormat>;
+}
+
+impl ElementBuffer for SimpleBuffer {
+    fn size(&self) -> u32 {
+        self.size
+    }
+
+    fn slice(&self) -> BufferSlice<'_> {
+        SimpleBuffer::slice(self)
+    }
+
+    fn index_format(&self) -> Option<IndexFormat> {
+        self.index_format
+    }
+}
+
+impl<T: Pod> ElementBuffer for DynamicBuffer<T> {
+    fn size(&self) -> u32 {
+        self.size
+    }
+
+    fn slice(&self) -> BufferSlice<'_> {
+        DynamicBuffer::slice(self)
+    }
+
+    fn index_format(&self) -> Option<IndexFormat> {
+        self.index_format
+    }
+}
+
+/// uniform-[Buffer] holding exactly one `T` which can be updated in place
+///
+/// `T` has to match the memory layout of the uniform in the shader (e.g. 16 byte alignment of vec3)
+pub struct UniformBuffer<T: Pod> {
+    buffer: Buffer,
+    phantom: PhantomData<T>,
+}
+
+impl<T: Pod> UniformBuffer<T> {
+    /// creates an [UniformBuffer] with [BufferUsages::UNIFORM] | [BufferUsages::COPY_DST]
+    pub fn new(device: &Device, data: &T) -> UniformBuffer<T> {
+        Self::with_label(device, data, "Uniform Buffer")
+    }
+
+    /// same as [UniformBuffer::new] but with a label (name of the buffer)
+    pub fn with_label(device: &Device, data: &T, label: &str) -> UniformBuffer<T> {
+        UniformBuffer {
+            buffer: device.create_buffer_init(&BufferInitDescriptor {
+                label: Some(label),
+                contents: bytemuck::bytes_of(data),
+                usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            }),
+            phantom: PhantomData,
+        }
+    }
+
+    /// overwrites the content with `data`
+    ///
+    /// the write happens before the next [Queue::submit]
+    pub fn write(&self, queue: &Queue, data: &T) {
+        queue.write_buffer(&self.buffer, 0, bytemuck::bytes_of(data));
+    }
+
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    /// [BindingResource] of the whole buffer
+    pub fn binding_resource(&self) -> BindingResource<'_> {
+        self.buffer.as_entire_binding()
+    }
+
+    /// [BindGroupEntry] of the whole buffer at the binding
+    pub fn bind_group_entry(&self, binding: u32) -> BindGroupEntry<'_> {
+        BindGroupEntry {
+            binding,
+            resource: self.binding_resource(),
+        }
+    }
+}
+
+/// [Buffer] with [BufferUsages::COPY_DST] which stores count of elements and can be updated in place
+///
+/// the buffer only gets recreated when [DynamicBuffer::replace] needs more than the current capacity.
+/// a copy of the contents is kept on the cpu, so writes do not have to be aligned to 4 bytes (e.g. single `u16` indices)
+pub struct DynamicBuffer<T: Pod> {
+    buffer: Buffer,
+    /// copy of the whole buffer, used to fill up unaligned writes
+    contents: Vec<u8>,
+    usage: BufferUsages,
+    index_format: Option<IndexFormat>,
+    label: String,
+
+    size: u32,
+    capacity: u32,
+
+    phantom: PhantomData<T>,
+}
+
+impl<T: Pod> DynamicBuffer<T> {
+    /// overwrites the elements starting at the element `offset` with `data`
+    ///
+    /// [DynamicBuffer::size] grows if elements past it are written
+    ///
+    /// # Panics
+    ///
+    /// if `offset + data.len()` is greater than [DynamicBuffer::capacity]
+    pub fn write(&mut self, queue: &Queue, offset: u32, data: &[T]) {
+        let end = offset + data.len() as u32;
+
+        assert!(
+            end <= self.capacity,
+            "write of elements {}..{} is out of the capacity {}",
+            offset,
+            end,
+            self.capacity
+        );
+
+        if data.is_empty() {
+            return;
+        }
+
+        let start = (offset as BufferAddress * element_size::<T>()) as usize;
+        let end_byte = (end as BufferAddress * element_size::<T>()) as usize;
+        self.contents[start..end_byte].copy_from_slice(bytemuck::cast_slice(data));
+
+        // widened to the alignment write_buffer needs, the surrounding bytes come from the copy
+        let aligned_start = start - start % COPY_BUFFER_ALIGNMENT as usize;
+        let aligned_end = align(end_byte as BufferAddress, COPY_BUFFER_ALIGNMENT) as usize;
+
+        queue.write_buffer(
+            &self.buffer,
+            aligned_start as BufferAddress,
+            &self.contents[aligned_start..aligned_end],
+        );
+        self.size = self.size.max(end);
+    }
+
+    /// replaces all elements with `data`
+    ///
+    /// the buffer is reused if `data` fits into the capacity, otherwise a new one with at least double the capacity is created
+    pub fn replace(&mut self, device: &Device, queue: &Queue, data: Vec<T>) {
+        let len = data.len() as u32;
+
+        if len > self.capacity {
+            self.capacity = len.max(self.capacity * 2);
+            self.buffer =
+                create_dynamic_buffer::<T>(device, &self.label, self.usage, self.capacity);
+            self.contents = vec![0; self.buffer.size() as usize];
+        }
+
+        if !data.is_empty() {
+            let bytes: &[u8] = bytemuck::cast_slice(&data);
+            self.contents[..bytes.len()].copy_from_slice(bytes);
+
+            let aligned_len = align(bytes.len() as BufferAddress, COPY_BUFFER_ALIGNMENT) as usize;
+            queue.write_buffer(&self.buffer, 0, &self.contents[..aligned_len]);
+        }
+
+        self.size = len;
+    }
+
+    /// count of elements
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// count of elements which fit into the buffer without recreating it
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// the [IndexFormat] of an indices-buffer, [None] for other buffers
+    pub fn index_format(&self) -> Option<IndexFormat> {
+        self.index_format
+    }
+
+    /// [BufferSlice] of the first [DynamicBuffer::size] elements
+    pub fn slice(&self) -> BufferSlice<'_> {
+        if self.size == 0 {
+            return self.buffer.slice(..);
+        }
+
+        self.buffer
+            .slice(..self.size as BufferAddress * element_size::<T>())
+    }
+
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+}
+
+fn element_size<T>() -> BufferAddress {
+    mem::size_of::<T>() as BufferAddress
+}
+
+/// rounds `size` up to a multiple of `alignment`
+///
+/// [Queue::write_buffer] only accepts offsets and sizes which are multiples of [COPY_BUFFER_ALIGNMENT]
+pub(crate) fn align(size: BufferAddress, alignment: BufferAddress) -> BufferAddress {
+    size.div_ceil(alignment) * alignment
+}
+
+fn create_dynamic_buffer<T>(
+    device: &Device,
+    label: &str,
+    usage: BufferUsages,
+    capacity: u32,
+) -> Buffer {
+    device.create_buffer(&BufferDescriptor {
+        label: Some(label),
+        size: align(
+            capacity as BufferAddress * element_size::<T>(),
+            COPY_BUFFER_ALIGNMENT,
+        ),
+        usage,
+        mapped_at_creation: false,
+    })
 }
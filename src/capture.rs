@@ -0,0 +1,229 @@
+use std::iter::once;
+use std::num::NonZeroU32;
+use std::sync::mpsc;
+
+use image::RgbaImage;
+use wgpu::{
+    BindGroup, BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Device, Extent3d,
+    ImageCopyBuffer, ImageDataLayout, Maintain, MapMode, Queue, RenderPipeline, Sampler,
+    SamplerDescriptor, ShaderStages, SurfaceConfiguration, Texture, TextureDescriptor,
+    TextureDimension, TextureFormat, TextureUsages, TextureView, TextureViewDescriptor,
+    COPY_BYTES_PER_ROW_ALIGNMENT,
+};
+
+use crate::bind_group::{BindGroupCreator, BindGroupLayoutCreator, SimpleBindGroupLayout};
+use crate::render_pass::RenderPassCreator;
+use crate::render_pipeline::RenderPipelineCreator;
+
+/// copies the content of a [Texture] back to the cpu and returns it as [RgbaImage]
+///
+/// the [Texture] needs [TextureUsages::COPY_SRC](wgpu::TextureUsages::COPY_SRC).
+/// the returned image can be saved as PNG with [RgbaImage::save]
+///
+/// # Panics
+///
+/// if the [TextureFormat] of the [Texture] is not one of
+/// [Rgba8Unorm](TextureFormat::Rgba8Unorm) | [Rgba8UnormSrgb](TextureFormat::Rgba8UnormSrgb) |
+/// [Bgra8Unorm](TextureFormat::Bgra8Unorm) | [Bgra8UnormSrgb](TextureFormat::Bgra8UnormSrgb)
+pub fn capture_texture(device: &Device, queue: &Queue, texture: &Texture) -> RgbaImage {
+    let swap_red_blue = match texture.format() {
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => false,
+        TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => true,
+        format => panic!("capturing a Texture with the format {:?} is not supported", format),
+    };
+
+    let size = texture.size();
+
+    let unpadded_bytes_per_row = size.width * 4;
+    let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(COPY_BYTES_PER_ROW_ALIGNMENT)
+        * COPY_BYTES_PER_ROW_ALIGNMENT;
+
+    let buffer = device.create_buffer(&BufferDescriptor {
+        label: Some("Capture Buffer"),
+        size: (padded_bytes_per_row * size.height) as u64,
+        usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("Capture Encoder"),
+    });
+
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        ImageCopyBuffer {
+            buffer: &buffer,
+            layout: ImageDataLayout {
+                offset: 0,
+                bytes_per_row: NonZeroU32::new(padded_bytes_per_row),
+                rows_per_image: None,
+            },
+        },
+        size,
+    );
+
+    queue.submit(once(encoder.finish()));
+
+    let slice = buffer.slice(..);
+
+    let (sender, receiver) = mpsc::channel();
+    slice.map_async(MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+
+    device.poll(Maintain::Wait);
+    receiver
+        .recv()
+        .expect("map_async callback was not called")
+        .expect("could not map Capture Buffer");
+
+    let mut pixels = Vec::with_capacity((unpadded_bytes_per_row * size.height) as usize);
+
+    for row in slice
+        .get_mapped_range()
+        .chunks(padded_bytes_per_row as usize)
+    {
+        pixels.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
+    }
+
+    buffer.unmap();
+
+    if swap_red_blue {
+        for pixel in pixels.chunks_mut(4) {
+            pixel.swap(0, 2);
+        }
+    }
+
+    RgbaImage::from_raw(size.width, size.height, pixels)
+        .expect("captured pixels do not match the texture size")
+}
+
+/// fullscreen triangle which samples the frame texture
+const FRAME_BLIT_SHADER: &str = "
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let tex_coords = vec2<f32>(f32(index & 2u), f32((index << 1u) & 2u));
+
+    var out: VertexOutput;
+    out.position = vec4<f32>(tex_coords * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0), 0.0, 1.0);
+    out.tex_coords = tex_coords;
+    return out;
+}
+
+@group(0) @binding(0)
+var frame: texture_2d<f32>;
+@group(0) @binding(1)
+var frame_sampler: sampler;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(frame, frame_sampler, in.tex_coords);
+}
+";
+
+/// lets the main window render into a texture which gets copied onto the surface, so the last frame can be [captured](crate::app::AppData::capture_frame)
+///
+/// the surface itself can not be copied from on most backends
+pub(crate) struct FrameBlit {
+    pipeline: RenderPipeline,
+    layout: SimpleBindGroupLayout,
+    sampler: Sampler,
+
+    frame: Option<(Texture, BindGroup)>,
+}
+
+impl FrameBlit {
+    pub(crate) fn new(device: &Device, config: &SurfaceConfiguration) -> FrameBlit {
+        let layout = BindGroupLayoutCreator::new(device)
+            .label("Frame Blit Bind Group Layout")
+            .add_texture(ShaderStages::FRAGMENT)
+            .add_sampler(ShaderStages::FRAGMENT)
+            .build();
+
+        let pipeline = RenderPipelineCreator::from_shader_code(FRAME_BLIT_SHADER, device, config)
+            .add_bind_group(layout.layout())
+            .build();
+
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            label: Some("Frame Blit Sampler"),
+            ..Default::default()
+        });
+
+        FrameBlit {
+            pipeline,
+            layout,
+            sampler,
+            frame: None,
+        }
+    }
+
+    /// the texture the frame gets rendered into, recreated when the size of the surface changed
+    pub(crate) fn frame(&mut self, device: &Device, config: &SurfaceConfiguration) -> &Texture {
+        let outdated = !self.frame.as_ref().is_some_and(|(texture, _)| {
+            let size = texture.size();
+            size.width == config.width && size.height == config.height
+        });
+
+        if outdated {
+            let texture = device.create_texture(&TextureDescriptor {
+                label: Some("Frame Texture"),
+                size: Extent3d {
+                    width: config.width,
+                    height: config.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: config.format,
+                usage: TextureUsages::RENDER_ATTACHMENT
+                    | TextureUsages::TEXTURE_BINDING
+                    | TextureUsages::COPY_SRC,
+                view_formats: &[],
+            });
+
+            let bind_group = BindGroupCreator::new(device, &self.layout)
+                .label("Frame Blit Bind Group")
+                .add_texture_view(&texture.create_view(&TextureViewDescriptor::default()))
+                .add_sampler(&self.sampler)
+                .build();
+
+            self.frame = Some((texture, bind_group));
+        }
+
+        &self.frame.as_ref().unwrap().0
+    }
+
+    /// the last frame, [None] before the first one got rendered
+    pub(crate) fn last_frame(&self) -> Option<&Texture> {
+        self.frame.as_ref().map(|(texture, _)| texture)
+    }
+
+    /// copies the frame onto the view of the surface
+    pub(crate) fn blit(&self, device: &Device, queue: &Queue, view: &TextureView) {
+        let Some((_, bind_group)) = &self.frame else {
+            return;
+        };
+
+        let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("Frame Blit Encoder"),
+        });
+
+        {
+            let mut render_pass = RenderPassCreator::new(view)
+                .label("Frame Blit")
+                .build(&mut encoder);
+
+            render_pass.set_pipeline(&self.pipeline);
+            render_pass.set_bind_group(0, bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+
+        queue.submit(once(encoder.finish()));
+    }
+}
@@ -0,0 +1,185 @@
+use wgpu::{
+    BindGroupLayout, ComputePass, ComputePipeline, ComputePipelineDescriptor, Device, ErrorFilter,
+    PipelineLayoutDescriptor, ShaderModule,
+};
+
+use crate::error::NoboilerError;
+use crate::render_pipeline::create_shader_module;
+use crate::shader_preprocessor::ShaderPreprocessor;
+
+/// Builder Patter for wgpu [ComputePipeline]
+pub struct ComputePipelineCreator<'a> {
+    device: &'a Device,
+
+    shader: ShaderModule,
+    entry_point: &'a str,
+
+    bind_groups: Vec<&'a BindGroupLayout>,
+
+    label: &'a str,
+}
+
+impl<'a> ComputePipelineCreator<'a> {
+    /// creates an [ComputePipelineCreator] where the shader is from the path
+    ///
+    /// the file gets [preprocessed](ShaderPreprocessor) so `#include` | `#define` | `#ifdef` can be used
+    ///
+    /// # Panics
+    ///
+    /// if the file could not be read or the shader could not be compiled, use [ComputePipelineCreator::try_from_shader_file] to handle the error
+    pub fn from_shader_file(path: &'a str, device: &'a Device) -> ComputePipelineCreator<'a> {
+        Self::try_from_shader_file(path, device).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// same as [ComputePipelineCreator::from_shader_file] but returns an [NoboilerError] on failure
+    pub fn try_from_shader_file(
+        path: &'a str,
+        device: &'a Device,
+    ) -> Result<ComputePipelineCreator<'a>, NoboilerError> {
+        Self::try_from_shader_file_with(path, &ShaderPreprocessor::new(), device)
+    }
+
+    /// same as [ComputePipelineCreator::from_shader_file] but uses the given [ShaderPreprocessor] (e.g. with defines)
+    ///
+    /// # Panics
+    ///
+    /// if the file could not be read or the shader could not be compiled, use [ComputePipelineCreator::try_from_shader_file_with] to handle the error
+    pub fn from_shader_file_with(
+        path: &'a str,
+        preprocessor: &ShaderPreprocessor,
+        device: &'a Device,
+    ) -> ComputePipelineCreator<'a> {
+        Self::try_from_shader_file_with(path, preprocessor, device)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// same as [ComputePipelineCreator::from_shader_file_with] but returns an [NoboilerError] on failure
+    pub fn try_from_shader_file_with(
+        path: &'a str,
+        preprocessor: &ShaderPreprocessor,
+        device: &'a Device,
+    ) -> Result<ComputePipelineCreator<'a>, NoboilerError> {
+        let shader = preprocessor.process_file(path)?;
+
+        Self::try_from_shader_code(shader.code(), device).map_err(|err| shader.map_error(err))
+    }
+
+    /// creates an [ComputePipelineCreator] where the shader is based on the given code
+    ///
+    /// # Panics
+    ///
+    /// if the shader could not be compiled, use [ComputePipelineCreator::try_from_shader_code] to handle the error
+    pub fn from_shader_code(shader_code: &str, device: &'a Device) -> ComputePipelineCreator<'a> {
+        Self::try_from_shader_code(shader_code, device).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// same as [ComputePipelineCreator::from_shader_code] but returns an [NoboilerError] if the shader could not be compiled
+    pub fn try_from_shader_code(
+        shader_code: &str,
+        device: &'a Device,
+    ) -> Result<ComputePipelineCreator<'a>, NoboilerError> {
+        let shader = create_shader_module(device, shader_code, "Compute Pipeline Shader")?;
+
+        Ok(ComputePipelineCreator {
+            device,
+            shader,
+            entry_point: "cs_main",
+
+            bind_groups: vec![],
+
+            label: "Compute Pipeline",
+        })
+    }
+
+    /// sets the name of the Compute-Main
+    ///
+    /// default: cs_main
+    pub fn entry_point(mut self, fn_name: &'a str) -> Self {
+        self.entry_point = fn_name;
+        self
+    }
+
+    /// adds a [BindGroupLayout] to the used list
+    pub fn add_bind_group(mut self, layout: &'a BindGroupLayout) -> Self {
+        self.bind_groups.push(layout);
+
+        self
+    }
+
+    /// sets label (name)
+    pub fn label(mut self, label: &'a str) -> Self {
+        self.label = label;
+        self
+    }
+
+    /// creates a [ComputePipeline]
+    ///
+    /// # Panics
+    ///
+    /// if the pipeline is invalid (e.g. wrong entry point), use [ComputePipelineCreator::try_build] to handle the error
+    pub fn build(&self) -> ComputePipeline {
+        self.try_build().unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// same as [ComputePipelineCreator::build] but returns an [NoboilerError] if the pipeline is invalid
+    pub fn try_build(&self) -> Result<ComputePipeline, NoboilerError> {
+        let layout = self.device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some(&(self.label.to_owned() + " Layout")),
+            bind_group_layouts: &self.bind_groups[..],
+            push_constant_ranges: &[],
+        });
+
+        self.device.push_error_scope(ErrorFilter::Validation);
+
+        let compute_pipeline = self
+            .device
+            .create_compute_pipeline(&ComputePipelineDescriptor {
+                label: Some(self.label),
+                layout: Some(&layout),
+                module: &self.shader,
+                entry_point: self.entry_point,
+            });
+
+        match pollster::block_on(self.device.pop_error_scope()) {
+            None => Ok(compute_pipeline),
+            Some(err) => Err(NoboilerError::Pipeline(err)),
+        }
+    }
+}
+
+/// count of workgroups needed so that every element of `problem_size` is covered
+///
+/// `workgroup_size` has to match the `@workgroup_size` of the shader
+///
+/// # Panics
+///
+/// if any component of `workgroup_size` is 0
+pub fn workgroup_count(problem_size: (u32, u32, u32), workgroup_size: (u32, u32, u32)) -> (u32, u32, u32) {
+    assert!(
+        workgroup_size.0 > 0 && workgroup_size.1 > 0 && workgroup_size.2 > 0,
+        "every component of the workgroup size has to be at least 1 but got {:?}",
+        workgroup_size
+    );
+
+    (
+        problem_size.0.div_ceil(workgroup_size.0),
+        problem_size.1.div_ceil(workgroup_size.1),
+        problem_size.2.div_ceil(workgroup_size.2),
+    )
+}
+
+/// records a dispatch with enough workgroups to cover `problem_size`, see [workgroup_count]
+///
+/// the shader has to ignore invocations outside of `problem_size`
+///
+/// # Panics
+///
+/// if any component of `workgroup_size` is 0
+pub fn dispatch(
+    compute_pass: &mut ComputePass,
+    problem_size: (u32, u32, u32),
+    workgroup_size: (u32, u32, u32),
+) {
+    let (x, y, z) = workgroup_count(problem_size, workgroup_size);
+    compute_pass.dispatch_workgroups(x, y, z);
+}
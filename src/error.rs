@@ -0,0 +1,173 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+use image::ImageError;
+use wgpu::{CreateSurfaceError, RequestDeviceError, TextureFormat};
+use winit::error::OsError;
+
+use crate::app::AppCreator;
+
+/// everything which can go wrong while setting up an [App](crate::app::App) or its pipelines
+#[derive(Debug)]
+pub enum NoboilerError {
+    /// the [Window](winit::window::Window) could not be created
+    WindowCreation(OsError),
+    /// the [Surface](wgpu::Surface) could not be created
+    SurfaceCreation(CreateSurfaceError),
+    /// the [Surface](wgpu::Surface) does not support any format on the chosen [Adapter](wgpu::Adapter)
+    SurfaceUnsupported,
+    /// no [Adapter](wgpu::Adapter) matches the requested options
+    AdapterNotFound,
+    /// the [Device](wgpu::Device) could not be requested from the [Adapter](wgpu::Adapter)
+    DeviceRequest(RequestDeviceError),
+    /// the format of the [depth buffer](AppCreator::depth_buffer) is no depth format or is not supported by the [Adapter](wgpu::Adapter)
+    DepthFormatUnsupported(TextureFormat),
+    /// the [sample count](AppCreator::sample_count) is not supported by the [Adapter](wgpu::Adapter) for the used formats
+    SampleCountUnsupported(u32),
+    /// a shader file could not be read
+    ShaderFile { path: PathBuf, error: io::Error },
+    /// a shader file could not be [preprocessed](crate::shader_preprocessor::ShaderPreprocessor)
+    Preprocessor {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    /// a shader could not be compiled
+    Shader(wgpu::Error),
+    /// a shader file could not be compiled, the location points into the original (not preprocessed) file
+    ShaderFileCompilation {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        error: wgpu::Error,
+    },
+    /// a pipeline could not be created
+    Pipeline(wgpu::Error),
+    /// the resources of a [BindGroupCreator](crate::bind_group::BindGroupCreator) do not match its layout
+    BindGroupMismatch { binding: u32, message: String },
+    /// a bind group could not be created
+    BindGroup(wgpu::Error),
+    /// there is no pipeline with the name in the [PipelineRegistry](crate::pipeline_registry::PipelineRegistry)
+    UnknownPipeline(String),
+    /// an image file could not be read or decoded into a [Texture](wgpu::Texture)
+    TextureFile { path: PathBuf, error: ImageError },
+    /// image bytes could not be decoded into a [Texture](wgpu::Texture)
+    Texture(ImageError),
+    /// the [Texture](wgpu::Texture) could not be created (e.g. the image is bigger than the limits of the [Device](wgpu::Device))
+    TextureCreation(wgpu::Error),
+}
+
+impl Display for NoboilerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NoboilerError::WindowCreation(err) => write!(f, "could not create window: {}", err),
+            NoboilerError::SurfaceCreation(err) => write!(f, "could not create surface: {}", err),
+            NoboilerError::SurfaceUnsupported => {
+                write!(f, "the surface is not supported by the adapter")
+            }
+            NoboilerError::AdapterNotFound => write!(f, "could not find a matching adapter"),
+            NoboilerError::DeviceRequest(err) => write!(f, "could not request device: {}", err),
+            NoboilerError::DepthFormatUnsupported(format) => {
+                write!(f, "{:?} can not be used as depth buffer", format)
+            }
+            NoboilerError::SampleCountUnsupported(count) => {
+                write!(f, "a sample count of {} is not supported by the adapter", count)
+            }
+            NoboilerError::ShaderFile { path, error } => write!(
+                f,
+                "could not read Shader-File at {}: {}",
+                path.display(),
+                error
+            ),
+            NoboilerError::Preprocessor {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+            NoboilerError::Shader(err) => write!(f, "could not compile shader: {}", err),
+            NoboilerError::ShaderFileCompilation {
+                path,
+                line,
+                column,
+                error,
+            } => write!(
+                f,
+                "could not compile shader at {}:{}:{}: {}",
+                path.display(),
+                line,
+                column,
+                error
+            ),
+            NoboilerError::Pipeline(err) => write!(f, "could not create pipeline: {}", err),
+            NoboilerError::BindGroupMismatch { binding, message } => {
+                write!(f, "binding {} does not match the layout: {}", binding, message)
+            }
+            NoboilerError::BindGroup(err) => write!(f, "could not create bind group: {}", err),
+            NoboilerError::UnknownPipeline(name) => {
+                write!(f, "there is no pipeline with the name \"{}\"", name)
+            }
+            NoboilerError::TextureFile { path, error } => write!(
+                f,
+                "could not load Texture-File at {}: {}",
+                path.display(),
+                error
+            ),
+            NoboilerError::Texture(err) => write!(f, "could not load texture: {}", err),
+            NoboilerError::TextureCreation(err) => write!(f, "could not create texture: {}", err),
+        }
+    }
+}
+
+impl Error for NoboilerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            NoboilerError::WindowCreation(err) => Some(err),
+            NoboilerError::SurfaceCreation(err) => Some(err),
+            NoboilerError::DeviceRequest(err) => Some(err),
+            NoboilerError::ShaderFile { error, .. } => Some(error),
+            NoboilerError::Shader(err)
+            | NoboilerError::Pipeline(err)
+            | NoboilerError::BindGroup(err)
+            | NoboilerError::TextureCreation(err) => Some(err),
+            NoboilerError::ShaderFileCompilation { error, .. } => Some(error),
+            NoboilerError::TextureFile { error, .. } | NoboilerError::Texture(error) => Some(error),
+            NoboilerError::SurfaceUnsupported
+            | NoboilerError::AdapterNotFound
+            | NoboilerError::DepthFormatUnsupported(_)
+            | NoboilerError::SampleCountUnsupported(_)
+            | NoboilerError::Preprocessor { .. }
+            | NoboilerError::BindGroupMismatch { .. }
+            | NoboilerError::UnknownPipeline(_) => None,
+        }
+    }
+}
+
+/// returned by [AppCreator::try_run] when the [App](crate::app::App) could not be started
+///
+/// contains the [AppCreator] so it can be adjusted (e.g. another [PowerPreference](wgpu::PowerPreference)) and run again
+pub struct RunError<T: 'static, E: 'static = ()> {
+    pub error: NoboilerError,
+    pub app_creator: Box<AppCreator<T, E>>,
+}
+
+impl<T: 'static, E: 'static> Debug for RunError<T, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RunError")
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: 'static, E: 'static> Display for RunError<T, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.error, f)
+    }
+}
+
+impl<T: 'static, E: 'static> Error for RunError<T, E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::VecDeque;
+
+/// durations of one frame in seconds
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct FrameTiming {
+    /// time since the previous frame
+    pub frame: f64,
+    /// duration of the [update](crate::app::AppCreator::update) (including [fixed updates](crate::app::AppCreator::fixed_update))
+    pub update: f64,
+    /// duration of the [render](crate::app::AppCreator::render)
+    pub render: f64,
+}
+
+/// statistics of one kind of duration in seconds
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct DurationStats {
+    pub average: f64,
+    pub min: f64,
+    pub max: f64,
+    /// average of the slowest 1% (at least one sample)
+    pub low_1_percent: f64,
+    /// average of the slowest 0.1% (at least one sample)
+    pub low_0_1_percent: f64,
+}
+
+/// rolling statistics over the [FrameTiming] of the last [capacity](FrameStats::capacity) frames
+///
+/// available as [AppData::frame_stats](crate::app::AppData::frame_stats), the capacity can be set with [AppCreator::frame_stats_capacity](crate::app::AppCreator::frame_stats_capacity)
+pub struct FrameStats {
+    capacity: usize,
+    timings: VecDeque<FrameTiming>,
+}
+
+impl FrameStats {
+    /// # Panics
+    ///
+    /// if `capacity` is 0
+    pub fn new(capacity: usize) -> FrameStats {
+        assert!(capacity > 0, "the capacity of FrameStats has to be greater than 0");
+
+        FrameStats {
+            capacity,
+            timings: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// adds the timing of a frame, the oldest one gets removed when the capacity is reached
+    pub fn push(&mut self, timing: FrameTiming) {
+        if self.timings.len() == self.capacity {
+            self.timings.pop_front();
+        }
+
+        self.timings.push_back(timing);
+    }
+
+    /// max count of frames the statistics are calculated over
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// count of recorded frames, at most [FrameStats::capacity]
+    pub fn len(&self) -> usize {
+        self.timings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timings.is_empty()
+    }
+
+    /// the recorded timings from oldest to newest
+    pub fn timings(&self) -> impl Iterator<Item = &FrameTiming> {
+        self.timings.iter()
+    }
+
+    /// the timing of the last frame
+    pub fn last(&self) -> Option<&FrameTiming> {
+        self.timings.back()
+    }
+
+    /// average frames per second over the recorded frames
+    pub fn fps(&self) -> f64 {
+        let total: f64 = self.timings.iter().map(|timing| timing.frame).sum();
+
+        if total > 0.0 {
+            self.timings.len() as f64 / total
+        } else {
+            0.0
+        }
+    }
+
+    /// statistics of the time between frames
+    pub fn frame(&self) -> DurationStats {
+        self.stats(|timing| timing.frame)
+    }
+
+    /// statistics of the update durations
+    pub fn update(&self) -> DurationStats {
+        self.stats(|timing| timing.update)
+    }
+
+    /// statistics of the render durations
+    pub fn render(&self) -> DurationStats {
+        self.stats(|timing| timing.render)
+    }
+
+    fn stats(&self, duration: impl Fn(&FrameTiming) -> f64) -> DurationStats {
+        if self.timings.is_empty() {
+            return DurationStats::default();
+        }
+
+        let mut durations: Vec<f64> = self.timings.iter().map(duration).collect();
+        // slowest first
+        durations.sort_by(|a, b| b.total_cmp(a));
+
+        let slowest_average = |fraction: f64| {
+            let count = ((durations.len() as f64 * fraction).ceil() as usize).max(1);
+            durations[..count].iter().sum::<f64>() / count as f64
+        };
+
+        DurationStats {
+            average: durations.iter().sum::<f64>() / durations.len() as f64,
+            min: durations[durations.len() - 1],
+            max: durations[0],
+            low_1_percent: slowest_average(0.01),
+            low_0_1_percent: slowest_average(0.001),
+        }
+    }
+}
+
+impl Default for FrameStats {
+    /// keeps the last 300 frames
+    fn default() -> Self {
+        FrameStats::new(300)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(frame: f64) -> FrameTiming {
+        FrameTiming {
+            frame,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn evicts_oldest_at_capacity() {
+        let mut stats = FrameStats::new(3);
+
+        for duration in [1.0, 2.0, 3.0, 4.0, 5.0] {
+            stats.push(frame(duration));
+        }
+
+        assert_eq!(stats.len(), 3);
+        assert_eq!(stats.capacity(), 3);
+        assert_eq!(
+            stats
+                .timings()
+                .map(|timing| timing.frame)
+                .collect::<Vec<_>>(),
+            [3.0, 4.0, 5.0]
+        );
+        assert_eq!(stats.last(), Some(&frame(5.0)));
+        assert_eq!(stats.frame().min, 3.0);
+    }
+
+    #[test]
+    fn fps_without_time() {
+        let mut stats = FrameStats::new(10);
+        assert_eq!(stats.fps(), 0.0);
+
+        stats.push(frame(0.0));
+        stats.push(frame(0.0));
+        assert_eq!(stats.fps(), 0.0);
+
+        stats.push(frame(0.5));
+        assert_eq!(stats.fps(), 6.0);
+    }
+
+    #[test]
+    fn low_percentiles_use_at_least_one_sample() {
+        let mut stats = FrameStats::new(300);
+
+        for duration in 1..=50 {
+            stats.push(frame(duration as f64));
+        }
+
+        let frame_stats = stats.frame();
+        assert_eq!(frame_stats.max, 50.0);
+        assert_eq!(frame_stats.min, 1.0);
+        assert_eq!(frame_stats.average, 25.5);
+        assert_eq!(frame_stats.low_1_percent, 50.0);
+        assert_eq!(frame_stats.low_0_1_percent, 50.0);
+
+        for duration in 51..=150 {
+            stats.push(frame(duration as f64));
+        }
+
+        // 1% of 150 samples gets rounded up to the 2 slowest
+        let frame_stats = stats.frame();
+        assert_eq!(frame_stats.low_1_percent, 149.5);
+        assert_eq!(frame_stats.low_0_1_percent, 150.0);
+    }
+
+    #[test]
+    fn empty_stats() {
+        let stats = FrameStats::default();
+
+        assert!(stats.is_empty());
+        assert_eq!(stats.frame(), DurationStats::default());
+    }
+}
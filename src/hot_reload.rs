@@ -0,0 +1,147 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use wgpu::{Device, PipelineLayout, RenderPipeline};
+
+use crate::error::NoboilerError;
+use crate::pipeline_registry::PipelineRegistry;
+use crate::render_pipeline::{create_shader_module, PipelineSettings};
+use crate::shader_preprocessor::{PreprocessedShader, ShaderPreprocessor};
+
+/// how often the shader files get checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// remembers [RenderPipelines](RenderPipeline) created from files and rebuilds them when the file changes
+pub(crate) struct ShaderWatcher {
+    pipelines: Vec<WatchedPipeline>,
+    last_poll: Instant,
+}
+
+struct WatchedPipeline {
+    name: String,
+    shader_file: ShaderFile,
+
+    layout: PipelineLayout,
+    settings: PipelineSettings,
+}
+
+/// a shader file together with everything it includes
+#[derive(Clone)]
+pub(crate) struct ShaderFile {
+    path: PathBuf,
+    preprocessor: ShaderPreprocessor,
+    /// every read file with its modification time
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl ShaderFile {
+    pub(crate) fn new(
+        path: PathBuf,
+        preprocessor: ShaderPreprocessor,
+        shader: &PreprocessedShader,
+    ) -> ShaderFile {
+        ShaderFile {
+            path,
+            preprocessor,
+            files: shader
+                .files()
+                .iter()
+                .map(|file| (file.clone(), modified(file)))
+                .collect(),
+        }
+    }
+
+    /// returns true once if any of the files got modified
+    fn changed(&mut self) -> bool {
+        let mut changed = false;
+
+        for (path, modified_at) in &mut self.files {
+            let modified = modified(path);
+
+            if modified != *modified_at {
+                *modified_at = modified;
+                changed = true;
+            }
+        }
+
+        changed
+    }
+}
+
+impl ShaderWatcher {
+    pub(crate) fn new() -> ShaderWatcher {
+        ShaderWatcher {
+            pipelines: vec![],
+            last_poll: Instant::now(),
+        }
+    }
+
+    pub(crate) fn watch(
+        &mut self,
+        name: String,
+        shader_file: ShaderFile,
+        layout: PipelineLayout,
+        settings: PipelineSettings,
+    ) {
+        self.pipelines.retain(|pipeline| pipeline.name != name);
+        self.pipelines.push(WatchedPipeline {
+            name,
+            shader_file,
+            layout,
+            settings,
+        });
+    }
+
+    /// rebuilds every pipeline whose shader file changed since the last call
+    ///
+    /// if the new shader fails the old [RenderPipeline] is kept and the error gets logged
+    pub(crate) fn reload(&mut self, device: &Device, render_pipelines: &mut PipelineRegistry) {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return;
+        }
+        self.last_poll = Instant::now();
+
+        for pipeline in &mut self.pipelines {
+            // checked first so a change made while the pipeline is removed is kept for later
+            if !render_pipelines.contains(&pipeline.name) {
+                continue;
+            }
+
+            if !pipeline.shader_file.changed() {
+                continue;
+            }
+
+            match pipeline.rebuild(device) {
+                Ok(render_pipeline) => {
+                    render_pipelines.insert(pipeline.name.clone(), render_pipeline);
+                    log::info!("reloaded shader {}", pipeline.shader_file.path.display());
+                }
+                Err(err) => log::error!("{}", err),
+            }
+        }
+    }
+}
+
+impl WatchedPipeline {
+    fn rebuild(&mut self, device: &Device) -> Result<RenderPipeline, NoboilerError> {
+        let shader_file = &mut self.shader_file;
+        let shader = shader_file.preprocessor.process_file(&shader_file.path)?;
+
+        // includes may have changed, so the watched files get updated as well
+        *shader_file = ShaderFile::new(
+            shader_file.path.clone(),
+            shader_file.preprocessor.clone(),
+            &shader,
+        );
+
+        let module = create_shader_module(device, shader.code(), "Render Pipeline Shader")
+            .map_err(|err| shader.map_error(err))?;
+
+        self.settings.try_create(device, &self.layout, &module)
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
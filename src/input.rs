@@ -0,0 +1,149 @@
+use std::collections::HashSet;
+
+use winit::dpi::PhysicalPosition;
+use winit::event::{ElementState, MouseButton, MouseScrollDelta, VirtualKeyCode, WindowEvent};
+
+/// keyboard and mouse state of the current frame, available as [AppData::input](crate::app::AppData::input)
+///
+/// every window has its own, so positions are always relative to the window which is currently handled
+///
+/// the `just_*` values and the scroll deltas only contain the events since the last frame
+#[derive(Default)]
+pub struct InputState {
+    keys_down: HashSet<VirtualKeyCode>,
+    keys_pressed: HashSet<VirtualKeyCode>,
+    keys_released: HashSet<VirtualKeyCode>,
+
+    mouse_position: Option<PhysicalPosition<f64>>,
+
+    mouse_buttons_down: HashSet<MouseButton>,
+    mouse_buttons_pressed: HashSet<MouseButton>,
+    mouse_buttons_released: HashSet<MouseButton>,
+
+    scroll_delta: (f32, f32),
+    scroll_pixel_delta: (f64, f64),
+}
+
+impl InputState {
+    /// true while the key is held
+    pub fn is_key_down(&self, key: VirtualKeyCode) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    /// true in the frame the key got pressed, key repeats are ignored
+    pub fn just_pressed(&self, key: VirtualKeyCode) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    /// true in the frame the key got released
+    pub fn just_released(&self, key: VirtualKeyCode) -> bool {
+        self.keys_released.contains(&key)
+    }
+
+    /// all keys which are currently held
+    pub fn keys_down(&self) -> impl Iterator<Item = &VirtualKeyCode> {
+        self.keys_down.iter()
+    }
+
+    /// position of the cursor in pixels relative to the top-left corner of the window
+    ///
+    /// [None] when the cursor is outside of the window
+    pub fn mouse_position(&self) -> Option<PhysicalPosition<f64>> {
+        self.mouse_position
+    }
+
+    /// true while the mouse button is held
+    pub fn is_mouse_button_down(&self, button: MouseButton) -> bool {
+        self.mouse_buttons_down.contains(&button)
+    }
+
+    /// true in the frame the mouse button got pressed
+    pub fn mouse_button_just_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_buttons_pressed.contains(&button)
+    }
+
+    /// true in the frame the mouse button got released
+    pub fn mouse_button_just_released(&self, button: MouseButton) -> bool {
+        self.mouse_buttons_released.contains(&button)
+    }
+
+    /// (horizontal, vertical) scroll since the last frame in lines
+    ///
+    /// only contains wheels which scroll in lines, see [InputState::scroll_pixel_delta] for touchpads
+    pub fn scroll_delta(&self) -> (f32, f32) {
+        self.scroll_delta
+    }
+
+    /// (horizontal, vertical) scroll since the last frame in pixels
+    ///
+    /// only contains devices which scroll in pixels (e.g. touchpads), see [InputState::scroll_delta] for wheels
+    pub fn scroll_pixel_delta(&self) -> (f64, f64) {
+        self.scroll_pixel_delta
+    }
+
+    pub(crate) fn handle_event(&mut self, event: &WindowEvent) {
+        match event {
+            WindowEvent::KeyboardInput { input, .. } => {
+                let Some(key) = input.virtual_keycode else {
+                    return;
+                };
+
+                match input.state {
+                    ElementState::Pressed => {
+                        if self.keys_down.insert(key) {
+                            self.keys_pressed.insert(key);
+                        }
+                    }
+                    ElementState::Released => {
+                        if self.keys_down.remove(&key) {
+                            self.keys_released.insert(key);
+                        }
+                    }
+                }
+            }
+            WindowEvent::CursorMoved { position, .. } => self.mouse_position = Some(*position),
+            WindowEvent::CursorLeft { .. } => self.mouse_position = None,
+            WindowEvent::MouseInput { state, button, .. } => match state {
+                ElementState::Pressed => {
+                    if self.mouse_buttons_down.insert(*button) {
+                        self.mouse_buttons_pressed.insert(*button);
+                    }
+                }
+                ElementState::Released => {
+                    if self.mouse_buttons_down.remove(button) {
+                        self.mouse_buttons_released.insert(*button);
+                    }
+                }
+            },
+            WindowEvent::MouseWheel { delta, .. } => match delta {
+                MouseScrollDelta::LineDelta(x, y) => {
+                    self.scroll_delta.0 += x;
+                    self.scroll_delta.1 += y;
+                }
+                MouseScrollDelta::PixelDelta(position) => {
+                    self.scroll_pixel_delta.0 += position.x;
+                    self.scroll_pixel_delta.1 += position.y;
+                }
+            },
+            WindowEvent::Focused(false) => {
+                // the release events get lost when the window is not focused, so nothing should stay held
+                self.keys_released.extend(self.keys_down.drain());
+                self.mouse_buttons_released
+                    .extend(self.mouse_buttons_down.drain());
+            }
+            _ => {}
+        }
+    }
+
+    /// clears everything which is only valid for one frame
+    pub(crate) fn end_frame(&mut self) {
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+
+        self.mouse_buttons_pressed.clear();
+        self.mouse_buttons_released.clear();
+
+        self.scroll_delta = (0.0, 0.0);
+        self.scroll_pixel_delta = (0.0, 0.0);
+    }
+}
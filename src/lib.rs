@@ -5,9 +5,28 @@
 //! heavily based on the [WGPU-Tutorial](https://sotrh.github.io/learn-wgpu/)
 
 pub mod app;
+pub mod bind_group;
 pub mod buffer;
+pub mod capture;
+pub mod compute_pipeline;
+pub mod error;
+pub mod frame_stats;
+mod hot_reload;
+pub mod input;
+pub mod pipeline_registry;
 pub mod render_pass;
 pub mod render_pipeline;
+pub mod shader_preprocessor;
+pub mod testing;
+pub mod texture;
+pub mod upload;
 pub mod vertex;
 
 extern crate core;
+
+/// used by the code generated by the derive macros
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+    pub use wgpu;
+}
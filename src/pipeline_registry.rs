@@ -0,0 +1,78 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops::Index;
+
+use wgpu::RenderPipeline;
+
+use crate::error::NoboilerError;
+
+/// all [RenderPipelines](RenderPipeline) of an [App](crate::app::App) by name
+#[derive(Default)]
+pub struct PipelineRegistry {
+    pipelines: HashMap<String, RenderPipeline>,
+    deferred: RefCell<Vec<(String, RenderPipeline)>>,
+}
+
+impl PipelineRegistry {
+    pub fn new() -> PipelineRegistry {
+        PipelineRegistry::default()
+    }
+
+    /// returns the [RenderPipeline] with the name
+    pub fn get(&self, name: &str) -> Result<&RenderPipeline, NoboilerError> {
+        self.pipelines
+            .get(name)
+            .ok_or_else(|| NoboilerError::UnknownPipeline(name.to_owned()))
+    }
+
+    /// adds the [RenderPipeline] under the name
+    ///
+    /// returns the [RenderPipeline] which was stored under the name before
+    pub fn insert(
+        &mut self,
+        name: impl Into<String>,
+        render_pipeline: RenderPipeline,
+    ) -> Option<RenderPipeline> {
+        self.pipelines.insert(name.into(), render_pipeline)
+    }
+
+    /// same as [PipelineRegistry::insert] but can be called with a shared reference (e.g. from [AppData](crate::app::AppData))
+    ///
+    /// the [RenderPipeline] gets added before the next frame
+    pub fn insert_deferred(&self, name: impl Into<String>, render_pipeline: RenderPipeline) {
+        self.deferred
+            .borrow_mut()
+            .push((name.into(), render_pipeline));
+    }
+
+    /// removes and returns the [RenderPipeline] with the name
+    pub fn remove(&mut self, name: &str) -> Option<RenderPipeline> {
+        self.pipelines.remove(name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.pipelines.contains_key(name)
+    }
+
+    /// names of all stored [RenderPipelines](RenderPipeline)
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.pipelines.keys().map(String::as_str)
+    }
+
+    pub(crate) fn apply_deferred(&mut self) {
+        for (name, render_pipeline) in self.deferred.get_mut().drain(..) {
+            self.pipelines.insert(name, render_pipeline);
+        }
+    }
+}
+
+impl Index<&str> for PipelineRegistry {
+    type Output = RenderPipeline;
+
+    /// # Panics
+    ///
+    /// if there is no [RenderPipeline] with the name, use [PipelineRegistry::get] to handle the error
+    fn index(&self, name: &str) -> &RenderPipeline {
+        self.get(name).unwrap_or_else(|err| panic!("{}", err))
+    }
+}
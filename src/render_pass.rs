@@ -1,11 +1,15 @@
 use wgpu::{
     Color, CommandEncoder, LoadOp, Operations, RenderPass, RenderPassColorAttachment,
-    RenderPassDepthStencilAttachment, RenderPassDescriptor, TextureView,
+    RenderPassDepthStencilAttachment, RenderPassDescriptor, TextureFormat, TextureView,
 };
 
+use crate::app::AppData;
+use crate::buffer::ElementBuffer;
+
 /// Builder Patter for wgpu [RenderPass]
 pub struct RenderPassCreator<'a> {
     view: &'a TextureView,
+    resolve_target: Option<&'a TextureView>,
 
     label: &'a str,
 
@@ -17,9 +21,13 @@ pub struct RenderPassCreator<'a> {
 }
 
 impl<'a> RenderPassCreator<'a> {
+    /// creates an [RenderPassCreator] which renders into the view
+    ///
+    /// if the [App](crate::app::App) uses a [sample count](crate::app::AppCreator::sample_count) above 1 use [RenderPassCreator::from_app] instead
     pub fn new(view: &'a TextureView) -> RenderPassCreator<'a> {
         RenderPassCreator {
             view,
+            resolve_target: None,
             label: "Render Pass",
             clear_color: Color::WHITE,
             depth_stencil_attachment: None,
@@ -27,6 +35,19 @@ impl<'a> RenderPassCreator<'a> {
         }
     }
 
+    /// creates an [RenderPassCreator] which uses everything the [App](crate::app::App) was configured with
+    ///
+    /// same as [RenderPassCreator::new] followed by [RenderPassCreator::with_msaa] and (if there is a depth buffer) [RenderPassCreator::with_depth]
+    pub fn from_app(view: &'a TextureView, app_data: &'a AppData) -> RenderPassCreator<'a> {
+        let render_pass_creator = Self::new(view).with_msaa(app_data);
+
+        if app_data.depth_view().is_some() {
+            render_pass_creator.with_depth(app_data)
+        } else {
+            render_pass_creator
+        }
+    }
+
     /// sets label (name)
     pub fn label(mut self, label: &'a str) -> Self {
         self.label = label;
@@ -48,16 +69,48 @@ impl<'a> RenderPassCreator<'a> {
         self
     }
 
+    /// uses the [depth buffer](crate::app::AppCreator::depth_buffer) of the [App](crate::app::App) and clears it to 1.0 (stencil to 0)
+    ///
+    /// # Panics
+    ///
+    /// if no depth buffer was set with [AppCreator::depth_buffer](crate::app::AppCreator::depth_buffer)
+    pub fn with_depth(self, app_data: &'a AppData) -> Self {
+        let (Some(view), Some(format)) = (app_data.depth_view(), app_data.depth_format()) else {
+            panic!("with_depth requires a depth buffer, see AppCreator::depth_buffer")
+        };
+
+        let has_stencil = matches!(
+            format,
+            TextureFormat::Depth24PlusStencil8 | TextureFormat::Depth32FloatStencil8
+        );
+
+        self.depth_stencil_attachment(RenderPassDepthStencilAttachment {
+            view,
+            depth_ops: Some(Operations {
+                load: LoadOp::Clear(1.0),
+                store: true,
+            }),
+            stencil_ops: has_stencil.then_some(Operations {
+                load: LoadOp::Clear(0),
+                store: true,
+            }),
+        })
+    }
+
+    /// renders into the [multisampled texture](crate::app::AppCreator::sample_count) of the [App](crate::app::App) and resolves it into the view
+    ///
+    /// does nothing when the sample count is 1, so it can always be used
+    pub fn with_msaa(mut self, app_data: &'a AppData) -> Self {
+        if let Some(msaa_view) = app_data.msaa_view() {
+            self.resolve_target = Some(self.view);
+            self.view = msaa_view;
+        }
+        self
+    }
+
     /// creates a [RenderPass]
     pub fn build(mut self, encoder: &'a mut CommandEncoder) -> RenderPass<'a> {
-        self.color_attachments.push(Some(RenderPassColorAttachment {
-            view: self.view,
-            resolve_target: None,
-            ops: Operations {
-                load: LoadOp::Clear(self.clear_color),
-                store: true,
-            },
-        }));
+        self.push_color_attachment();
 
         let descriptor = RenderPassDescriptor {
             label: Some(self.label),
@@ -70,19 +123,71 @@ impl<'a> RenderPassCreator<'a> {
 
     /// returns the [RenderPassDescriptor]
     pub fn descriptor(&'a mut self) -> RenderPassDescriptor<'a, 'a> {
+        self.push_color_attachment();
+
+        RenderPassDescriptor {
+            label: Some(self.label),
+            color_attachments: &self.color_attachments,
+            depth_stencil_attachment: self.depth_stencil_attachment.clone(),
+        }
+    }
+
+    fn push_color_attachment(&mut self) {
         self.color_attachments.push(Some(RenderPassColorAttachment {
             view: self.view,
-            resolve_target: None,
+            resolve_target: self.resolve_target,
             ops: Operations {
                 load: LoadOp::Clear(self.clear_color),
                 store: true,
             },
         }));
+    }
+}
 
-        RenderPassDescriptor {
-            label: Some(self.label),
-            color_attachments: &self.color_attachments,
-            depth_stencil_attachment: self.depth_stencil_attachment.clone(),
-        }
+/// helpers for [RenderPass] which use the element counts of the buffers
+pub trait RenderPassExt<'a> {
+    /// sets `vertices` at slot 0 and `instances` at slot 1 and draws every vertex for every instance
+    ///
+    /// the layouts have to be added in the same order (see [RenderPipelineCreator::add_instance_buffer](crate::render_pipeline::RenderPipelineCreator::add_instance_buffer))
+    fn draw_instanced(
+        &mut self,
+        vertices: &'a impl ElementBuffer,
+        instances: &'a impl ElementBuffer,
+    );
+
+    /// sets the indices-buffer with its [IndexFormat](wgpu::IndexFormat)
+    ///
+    /// # Panics
+    ///
+    /// if the buffer was not created with [BufferCreator::indices](crate::buffer::BufferCreator::indices)
+    fn set_indices(&mut self, indices: &'a impl ElementBuffer);
+
+    /// [sets the indices](RenderPassExt::set_indices) and draws all of them once
+    fn draw_indices(&mut self, indices: &'a impl ElementBuffer);
+}
+
+impl<'a> RenderPassExt<'a> for RenderPass<'a> {
+    fn draw_instanced(
+        &mut self,
+        vertices: &'a impl ElementBuffer,
+        instances: &'a impl ElementBuffer,
+    ) {
+        self.set_vertex_buffer(0, vertices.slice());
+        self.set_vertex_buffer(1, instances.slice());
+
+        self.draw(0..vertices.size(), 0..instances.size());
+    }
+
+    fn set_indices(&mut self, indices: &'a impl ElementBuffer) {
+        let index_format = indices
+            .index_format()
+            .expect("set_indices requires a buffer created with BufferCreator::indices");
+
+        self.set_index_buffer(indices.slice(), index_format);
+    }
+
+    fn draw_indices(&mut self, indices: &'a impl ElementBuffer) {
+        self.set_indices(indices);
+        self.draw_indexed(0..indices.size(), 0, 0..1);
     }
 }
@@ -1,83 +1,193 @@
 use std::borrow::Cow;
 use std::fs;
+use std::path::{Path, PathBuf};
 
 use wgpu::{
-    BindGroupLayout, BlendState, ColorTargetState, ColorWrites, DepthStencilState, Device, Face,
-    FragmentState, FrontFace, MultisampleState, PipelineLayoutDescriptor, PolygonMode,
-    PrimitiveState, PrimitiveTopology, RenderPipeline, RenderPipelineDescriptor, ShaderModule,
-    ShaderModuleDescriptor, ShaderSource, SurfaceConfiguration, TextureFormat, VertexBufferLayout,
-    VertexState,
+    BindGroupLayout, BlendState, BufferAddress, ColorTargetState, ColorWrites, CompareFunction,
+    DepthBiasState, DepthStencilState, Device, ErrorFilter, Face, FragmentState, FrontFace,
+    MultisampleState, PipelineLayout, PipelineLayoutDescriptor, PolygonMode, PrimitiveState,
+    PrimitiveTopology, RenderPipeline, RenderPipelineDescriptor, ShaderModule,
+    ShaderModuleDescriptor, ShaderSource, StencilState, SurfaceConfiguration, TextureFormat,
+    VertexAttribute, VertexBufferLayout, VertexState, VertexStepMode,
 };
 
+use crate::app::AppData;
+use crate::error::NoboilerError;
+use crate::hot_reload::ShaderFile;
+use crate::pipeline_registry::PipelineRegistry;
+use crate::shader_preprocessor::ShaderPreprocessor;
+
 /// Builder Patter for wgpu [RenderPipeline]
 pub struct RenderPipelineCreator<'a> {
     device: &'a Device,
-    format: &'a TextureFormat,
 
     shader: ShaderModule,
-    vertex_main: &'a str,
-    fragment_main: &'a str,
+    shader_file: Option<ShaderFile>,
 
-    vertex_buffers: Vec<VertexBufferLayout<'a>>,
     bind_groups: Vec<&'a BindGroupLayout>,
 
-    depth_stencil: Option<DepthStencilState>,
-
-    label: &'a str,
-
-    blend_state: BlendState,
+    settings: PipelineSettings,
 }
 
 impl<'a> RenderPipelineCreator<'a> {
     /// creates an [RenderPipelineCreator] where the shader is from the path
+    ///
+    /// the file gets [preprocessed](ShaderPreprocessor) so `#include` | `#define` | `#ifdef` can be used
+    ///
+    /// # Panics
+    ///
+    /// if the file could not be read or the shader could not be compiled, use [RenderPipelineCreator::try_from_shader_file] to handle the error
     pub fn from_shader_file(
         path: &'a str,
         device: &'a Device,
-        config: &'a SurfaceConfiguration,
+        target: &impl PipelineTarget,
     ) -> RenderPipelineCreator<'a> {
-        Self::from_shader_code(
-            &fs::read_to_string(path)
-                .unwrap_or_else(|_| panic!("Could not find Shader-File at {}", path)),
-            device,
-            config,
-        )
+        Self::try_from_shader_file(path, device, target).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// same as [RenderPipelineCreator::from_shader_file] but returns an [NoboilerError] on failure
+    pub fn try_from_shader_file(
+        path: &'a str,
+        device: &'a Device,
+        target: &impl PipelineTarget,
+    ) -> Result<RenderPipelineCreator<'a>, NoboilerError> {
+        Self::try_from_shader_file_with(path, &ShaderPreprocessor::new(), device, target)
+    }
+
+    /// same as [RenderPipelineCreator::from_shader_file] but uses the given [ShaderPreprocessor] (e.g. with defines)
+    ///
+    /// # Panics
+    ///
+    /// if the file could not be read or the shader could not be compiled, use [RenderPipelineCreator::try_from_shader_file_with] to handle the error
+    pub fn from_shader_file_with(
+        path: &'a str,
+        preprocessor: &ShaderPreprocessor,
+        device: &'a Device,
+        target: &impl PipelineTarget,
+    ) -> RenderPipelineCreator<'a> {
+        Self::try_from_shader_file_with(path, preprocessor, device, target)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// same as [RenderPipelineCreator::from_shader_file_with] but returns an [NoboilerError] on failure
+    ///
+    /// compile errors point at the line in the original file
+    pub fn try_from_shader_file_with(
+        path: &'a str,
+        preprocessor: &ShaderPreprocessor,
+        device: &'a Device,
+        target: &impl PipelineTarget,
+    ) -> Result<RenderPipelineCreator<'a>, NoboilerError> {
+        let shader = preprocessor.process_file(path)?;
+
+        let mut creator = Self::try_from_shader_code(shader.code(), device, target)
+            .map_err(|err| shader.map_error(err))?;
+
+        creator.shader_file = Some(ShaderFile::new(
+            PathBuf::from(path),
+            preprocessor.clone(),
+            &shader,
+        ));
+
+        Ok(creator)
     }
 
     /// creates an [RenderPipelineCreator] where the shader is based on the given code
+    ///
+    /// # Panics
+    ///
+    /// if the shader could not be compiled, use [RenderPipelineCreator::try_from_shader_code] to handle the error
     pub fn from_shader_code(
         shader_code: &str,
         device: &'a Device,
-        config: &'a SurfaceConfiguration,
+        target: &impl PipelineTarget,
     ) -> RenderPipelineCreator<'a> {
-        let shader = device.create_shader_module(ShaderModuleDescriptor {
-            label: Some("Render Pipeline Shader"),
-            source: ShaderSource::Wgsl(Cow::from(shader_code)),
-        });
+        Self::try_from_shader_code(shader_code, device, target)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// same as [RenderPipelineCreator::from_shader_code] but returns an [NoboilerError] if the shader could not be compiled
+    pub fn try_from_shader_code(
+        shader_code: &str,
+        device: &'a Device,
+        target: &impl PipelineTarget,
+    ) -> Result<RenderPipelineCreator<'a>, NoboilerError> {
+        let shader = create_shader_module(device, shader_code, "Render Pipeline Shader")?;
 
-        RenderPipelineCreator {
+        Ok(RenderPipelineCreator {
             device,
-            format: &config.format,
             shader,
-            vertex_main: "vs_main",
-            fragment_main: "fs_main",
+            shader_file: None,
 
-            vertex_buffers: vec![],
             bind_groups: vec![],
 
-            depth_stencil: None,
+            settings: PipelineSettings {
+                format: target.format(),
+                sample_count: target.sample_count(),
+                vertex_main: "vs_main".to_owned(),
+                fragment_main: "fs_main".to_owned(),
 
-            label: "Render Pipeline",
-            blend_state: BlendState::REPLACE,
-        }
+                vertex_buffers: vec![],
+
+                depth_stencil: target.depth_format().map(depth_stencil_state),
+
+                label: "Render Pipeline".to_owned(),
+                blend_state: BlendState::REPLACE,
+            },
+        })
     }
 
     /// adds a [VertexBufferLayout] to the used list
+    ///
+    /// if its shader locations overlap with the ones of the previous layouts, all of its locations get shifted behind them
+    /// (e.g. an instance layout with the locations 0, 1 after a vertex layout with 0, 1 uses 2, 3)
     pub fn add_vertex_buffer(mut self, layout: VertexBufferLayout<'a>) -> Self {
-        self.vertex_buffers.push(layout);
+        let mut attributes = layout.attributes.to_vec();
+
+        let used_locations: Vec<u32> = self
+            .settings
+            .vertex_buffers
+            .iter()
+            .flat_map(|layout| layout.attributes.iter())
+            .map(|attribute| attribute.shader_location)
+            .collect();
+
+        let overlaps = attributes
+            .iter()
+            .any(|attribute| used_locations.contains(&attribute.shader_location));
+
+        if overlaps {
+            let next_location = used_locations.iter().max().map_or(0, |location| location + 1);
+            let first_location = attributes
+                .iter()
+                .map(|attribute| attribute.shader_location)
+                .min()
+                .unwrap_or(0);
+
+            for attribute in &mut attributes {
+                attribute.shader_location += next_location - first_location;
+            }
+        }
+
+        self.settings.vertex_buffers.push(OwnedVertexBufferLayout {
+            array_stride: layout.array_stride,
+            step_mode: layout.step_mode,
+            attributes,
+        });
 
         self
     }
 
+    /// same as [RenderPipelineCreator::add_vertex_buffer] but the buffer is stepped per instance ([VertexStepMode::Instance])
+    ///
+    /// use it with [BufferCreator::instance](crate::buffer::BufferCreator::instance)
+    pub fn add_instance_buffer(self, layout: VertexBufferLayout<'a>) -> Self {
+        self.add_vertex_buffer(VertexBufferLayout {
+            step_mode: VertexStepMode::Instance,
+            ..layout
+        })
+    }
+
     /// adds a [BindGroupLayout] to the used list
     pub fn add_bind_group(mut self, layout: &'a BindGroupLayout) -> Self {
         self.bind_groups.push(layout);
@@ -87,52 +197,228 @@ impl<'a> RenderPipelineCreator<'a> {
 
     /// sets the name of the Fragment-Main
     pub fn fragment_main(mut self, fn_name: &'a str) -> Self {
-        self.fragment_main = fn_name;
+        self.settings.fragment_main = fn_name.to_owned();
         self
     }
 
     /// sets the name of the Vertex-Main
     pub fn vertex_main(mut self, fn_name: &'a str) -> Self {
-        self.vertex_main = fn_name;
+        self.settings.vertex_main = fn_name.to_owned();
         self
     }
 
     /// sets the used [DepthStencilState]
+    ///
+    /// default: the one of [RenderPipelineCreator::with_depth] if the target has a [depth format](PipelineTarget::depth_format), otherwise none
     pub fn depth_stencil(mut self, depth_stencil: DepthStencilState) -> Self {
-        self.depth_stencil = Some(depth_stencil);
+        self.settings.depth_stencil = Some(depth_stencil);
+        self
+    }
+
+    /// sets a [DepthStencilState] matching the [depth buffer](crate::app::AppCreator::depth_buffer) of the [App](crate::app::App)
+    ///
+    /// fragments closer to the camera (less depth) win, the depth gets written
+    ///
+    /// pipelines created from the [AppData] already use it, this is needed for other targets (e.g. a [SurfaceConfiguration])
+    ///
+    /// # Panics
+    ///
+    /// if no depth buffer was set with [AppCreator::depth_buffer](crate::app::AppCreator::depth_buffer)
+    pub fn with_depth(self, app_data: &AppData) -> Self {
+        let format = app_data
+            .depth_format()
+            .expect("with_depth requires a depth buffer, see AppCreator::depth_buffer");
+
+        self.depth_stencil(depth_stencil_state(format))
+    }
+
+    /// sets the number of samples per pixel, has to match the render pass
+    ///
+    /// default: the [sample count](crate::app::AppCreator::sample_count) of the target, 1 for a [SurfaceConfiguration]
+    pub fn sample_count(mut self, sample_count: u32) -> Self {
+        self.settings.sample_count = sample_count;
         self
     }
 
     /// sets the used [BlendState]
     pub fn blend_state(mut self, blend_state: BlendState) -> Self {
-        self.blend_state = blend_state;
+        self.settings.blend_state = blend_state;
         self
     }
 
     /// creates a [RenderPipeline]
+    ///
+    /// # Panics
+    ///
+    /// if the pipeline is invalid (e.g. wrong entry points), use [RenderPipelineCreator::try_build] to handle the error
     pub fn build(&self) -> RenderPipeline {
-        let render_pipeline_layout =
-            self.device
-                .create_pipeline_layout(&PipelineLayoutDescriptor {
-                    label: Some(&(self.label.to_owned() + " Layout")),
-                    bind_group_layouts: &self.bind_groups[..],
-                    push_constant_ranges: &[],
-                });
+        self.try_build().unwrap_or_else(|err| panic!("{}", err))
+    }
 
+    /// same as [RenderPipelineCreator::build] but returns an [NoboilerError] if the pipeline is invalid
+    pub fn try_build(&self) -> Result<RenderPipeline, NoboilerError> {
+        self.settings
+            .try_create(self.device, &self.create_layout(), &self.shader)
+    }
+
+    /// creates a [RenderPipeline], inserts it into `render_pipelines` under the name and remembers it for [shader hot-reload](crate::app::AppCreator::watch_shaders)
+    ///
+    /// only pipelines created [from a file](RenderPipelineCreator::from_shader_file) get reloaded, included files are watched as well
+    ///
+    /// # Panics
+    ///
+    /// if the pipeline is invalid, use [RenderPipelineCreator::try_build_watched] to handle the error
+    pub fn build_watched(
+        &self,
+        name: &str,
+        app_data: &AppData,
+        render_pipelines: &mut PipelineRegistry,
+    ) {
+        self.try_build_watched(name, app_data, render_pipelines)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// same as [RenderPipelineCreator::build_watched] but returns an [NoboilerError] if the pipeline is invalid
+    pub fn try_build_watched(
+        &self,
+        name: &str,
+        app_data: &AppData,
+        render_pipelines: &mut PipelineRegistry,
+    ) -> Result<(), NoboilerError> {
+        let layout = self.create_layout();
+        let render_pipeline = self.settings.try_create(self.device, &layout, &self.shader)?;
+
+        if let Some(shader_file) = &self.shader_file {
+            app_data.shader_watcher.borrow_mut().watch(
+                name.to_owned(),
+                shader_file.clone(),
+                layout,
+                self.settings.clone(),
+            );
+        }
+
+        render_pipelines.insert(name, render_pipeline);
+
+        Ok(())
+    }
+
+    fn create_layout(&self) -> PipelineLayout {
         self.device
+            .create_pipeline_layout(&PipelineLayoutDescriptor {
+                label: Some(&(self.settings.label.to_owned() + " Layout")),
+                bind_group_layouts: &self.bind_groups[..],
+                push_constant_ranges: &[],
+            })
+    }
+}
+
+/// what a [RenderPipeline] renders into
+///
+/// use the [AppData] so the pipeline matches the [sample count](crate::app::AppCreator::sample_count) of the [App](crate::app::App)
+pub trait PipelineTarget {
+    /// [TextureFormat] of the color target
+    fn format(&self) -> TextureFormat;
+
+    /// number of samples per pixel
+    fn sample_count(&self) -> u32 {
+        1
+    }
+
+    /// [TextureFormat] of the depth buffer, pipelines created for the target test against it by default
+    fn depth_format(&self) -> Option<TextureFormat> {
+        None
+    }
+}
+
+impl PipelineTarget for SurfaceConfiguration {
+    fn format(&self) -> TextureFormat {
+        self.format
+    }
+}
+
+impl PipelineTarget for AppData {
+    fn format(&self) -> TextureFormat {
+        self.config.format
+    }
+
+    fn sample_count(&self) -> u32 {
+        self.sample_count()
+    }
+
+    fn depth_format(&self) -> Option<TextureFormat> {
+        self.depth_format()
+    }
+}
+
+/// closer fragments (less depth) win, the depth gets written
+fn depth_stencil_state(format: TextureFormat) -> DepthStencilState {
+    DepthStencilState {
+        format,
+        depth_write_enabled: true,
+        depth_compare: CompareFunction::Less,
+        stencil: StencilState::default(),
+        bias: DepthBiasState::default(),
+    }
+}
+
+/// everything of a [RenderPipelineCreator] which is needed to rebuild the [RenderPipeline] with another shader
+#[derive(Clone)]
+pub(crate) struct PipelineSettings {
+    format: TextureFormat,
+    sample_count: u32,
+
+    vertex_main: String,
+    fragment_main: String,
+
+    vertex_buffers: Vec<OwnedVertexBufferLayout>,
+
+    depth_stencil: Option<DepthStencilState>,
+
+    label: String,
+
+    blend_state: BlendState,
+}
+
+#[derive(Clone)]
+struct OwnedVertexBufferLayout {
+    array_stride: BufferAddress,
+    step_mode: VertexStepMode,
+    attributes: Vec<VertexAttribute>,
+}
+
+impl PipelineSettings {
+    pub(crate) fn try_create(
+        &self,
+        device: &Device,
+        layout: &PipelineLayout,
+        shader: &ShaderModule,
+    ) -> Result<RenderPipeline, NoboilerError> {
+        let vertex_buffers: Vec<VertexBufferLayout> = self
+            .vertex_buffers
+            .iter()
+            .map(|layout| VertexBufferLayout {
+                array_stride: layout.array_stride,
+                step_mode: layout.step_mode,
+                attributes: &layout.attributes,
+            })
+            .collect();
+
+        device.push_error_scope(ErrorFilter::Validation);
+
+        let render_pipeline = device
             .create_render_pipeline(&RenderPipelineDescriptor {
-                label: Some(self.label),
-                layout: Some(&render_pipeline_layout),
+                label: Some(&self.label),
+                layout: Some(layout),
                 vertex: VertexState {
-                    module: &self.shader,
-                    entry_point: self.vertex_main,
-                    buffers: &self.vertex_buffers[..],
+                    module: shader,
+                    entry_point: &self.vertex_main,
+                    buffers: &vertex_buffers[..],
                 },
                 fragment: Some(FragmentState {
-                    module: &self.shader,
-                    entry_point: self.fragment_main,
+                    module: shader,
+                    entry_point: &self.fragment_main,
                     targets: &[Some(ColorTargetState {
-                        format: self.format.to_owned(),
+                        format: self.format,
                         blend: Some(self.blend_state),
                         write_mask: ColorWrites::ALL,
                     })],
@@ -148,11 +434,41 @@ impl<'a> RenderPipelineCreator<'a> {
                 },
                 depth_stencil: self.depth_stencil.to_owned(),
                 multisample: MultisampleState {
-                    count: 1,
+                    count: self.sample_count,
                     mask: !0,
                     alpha_to_coverage_enabled: false,
                 },
                 multiview: None,
-            })
+            });
+
+        match pollster::block_on(device.pop_error_scope()) {
+            None => Ok(render_pipeline),
+            Some(err) => Err(NoboilerError::Pipeline(err)),
+        }
+    }
+}
+
+pub(crate) fn read_shader_file(path: &Path) -> Result<String, NoboilerError> {
+    fs::read_to_string(path).map_err(|error| NoboilerError::ShaderFile {
+        path: path.to_path_buf(),
+        error,
+    })
+}
+
+pub(crate) fn create_shader_module(
+    device: &Device,
+    shader_code: &str,
+    label: &str,
+) -> Result<ShaderModule, NoboilerError> {
+    device.push_error_scope(ErrorFilter::Validation);
+
+    let shader = device.create_shader_module(ShaderModuleDescriptor {
+        label: Some(label),
+        source: ShaderSource::Wgsl(Cow::from(shader_code)),
+    });
+
+    match pollster::block_on(device.pop_error_scope()) {
+        None => Ok(shader),
+        Some(err) => Err(NoboilerError::Shader(err)),
     }
 }
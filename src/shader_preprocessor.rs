@@ -0,0 +1,508 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::NoboilerError;
+use crate::render_pipeline::read_shader_file;
+
+/// small preprocessor for WGSL files
+///
+/// supported directives (each on its own line):
+/// * `#include "path"` - path is relative to the including file, every file is only included once
+/// * `#define NAME [value]` | `#undef NAME` - defines with a value get substituted in the following code
+/// * `#ifdef NAME` | `#ifndef NAME` | `#else` | `#endif`
+///
+/// a file which got included before is skipped silently, even when the defines changed since then
+/// (so its code only ever reflects the defines of the first include)
+#[derive(Clone, Default)]
+pub struct ShaderPreprocessor {
+    defines: HashMap<String, String>,
+}
+
+impl ShaderPreprocessor {
+    pub fn new() -> ShaderPreprocessor {
+        ShaderPreprocessor::default()
+    }
+
+    /// defines `name` before processing, same as `#define name value` at the top of the file
+    ///
+    /// use an empty value to only define the name (for `#ifdef`)
+    pub fn define(mut self, name: &str, value: &str) -> Self {
+        self.defines.insert(name.to_owned(), value.to_owned());
+        self
+    }
+
+    /// reads and processes the file at the path
+    pub fn process_file(&self, path: impl AsRef<Path>) -> Result<PreprocessedShader, NoboilerError> {
+        let mut processor = Processor {
+            defines: self.defines.clone(),
+            stack: vec![],
+            canonical_files: vec![],
+            shader: PreprocessedShader {
+                code: String::new(),
+                files: vec![],
+                lines: vec![],
+            },
+        };
+
+        processor.process(path.as_ref())?;
+
+        Ok(processor.shader)
+    }
+}
+
+/// result of [ShaderPreprocessor::process_file]
+pub struct PreprocessedShader {
+    code: String,
+    files: Vec<PathBuf>,
+    /// (index into files, line in that file) of every output line
+    lines: Vec<(usize, usize)>,
+}
+
+impl PreprocessedShader {
+    /// the processed WGSL code
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// every file which got read, starting with the processed file itself
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// maps a (1-based) line of [PreprocessedShader::code] back to the file and line it came from
+    pub fn map_line(&self, line: usize) -> Option<(&Path, usize)> {
+        let (file, line) = *self.lines.get(line.checked_sub(1)?)?;
+        Some((&self.files[file], line))
+    }
+
+    /// turns a [NoboilerError::Shader] into [NoboilerError::ShaderFileCompilation] pointing at the original file
+    pub(crate) fn map_error(&self, err: NoboilerError) -> NoboilerError {
+        let NoboilerError::Shader(error) = err else {
+            return err;
+        };
+
+        let location = find_location(&error.to_string()).and_then(|(line, column)| {
+            self.map_line(line)
+                .map(|(path, line)| (path.to_path_buf(), line, column))
+        });
+
+        match location {
+            Some((path, line, column)) => NoboilerError::ShaderFileCompilation {
+                path,
+                line,
+                column,
+                error,
+            },
+            None => NoboilerError::Shader(error),
+        }
+    }
+}
+
+struct Processor {
+    defines: HashMap<String, String>,
+    /// canonical paths of the files which are currently being processed
+    stack: Vec<PathBuf>,
+    canonical_files: Vec<PathBuf>,
+    shader: PreprocessedShader,
+}
+
+struct Condition {
+    active: bool,
+    has_else: bool,
+    line: usize,
+}
+
+impl Processor {
+    fn process(&mut self, path: &Path) -> Result<(), NoboilerError> {
+        let canonical = fs::canonicalize(path).map_err(|error| NoboilerError::ShaderFile {
+            path: path.to_path_buf(),
+            error,
+        })?;
+
+        if self.canonical_files.contains(&canonical) {
+            return Ok(());
+        }
+
+        let code = read_shader_file(path)?;
+
+        let file = self.shader.files.len();
+        self.shader.files.push(path.to_path_buf());
+        self.canonical_files.push(canonical.clone());
+        self.stack.push(canonical);
+
+        let error = |line: usize, message: String| NoboilerError::Preprocessor {
+            path: path.to_path_buf(),
+            line,
+            message,
+        };
+
+        let mut conditions: Vec<Condition> = vec![];
+
+        for (index, text) in code.lines().enumerate() {
+            let line = index + 1;
+            let active = conditions.iter().all(|condition| condition.active);
+
+            let Some(directive) = text.trim_start().strip_prefix('#') else {
+                if active {
+                    self.push_line(text, file, line);
+                }
+                continue;
+            };
+
+            let (name, argument) = directive
+                .trim()
+                .split_once(char::is_whitespace)
+                .map(|(name, argument)| (name, argument.trim()))
+                .unwrap_or((directive.trim(), ""));
+
+            match name {
+                "ifdef" | "ifndef" => {
+                    let defined = self.defines.contains_key(argument);
+                    conditions.push(Condition {
+                        active: defined == (name == "ifdef"),
+                        has_else: false,
+                        line,
+                    });
+                }
+                "else" => {
+                    let condition = conditions
+                        .last_mut()
+                        .ok_or_else(|| error(line, "#else without #ifdef".to_owned()))?;
+
+                    if condition.has_else {
+                        return Err(error(line, "second #else for the same #ifdef".to_owned()));
+                    }
+
+                    condition.active = !condition.active;
+                    condition.has_else = true;
+                }
+                "endif" => {
+                    conditions
+                        .pop()
+                        .ok_or_else(|| error(line, "#endif without #ifdef".to_owned()))?;
+                }
+                _ if !active => {}
+                "define" => {
+                    let (define, value) = argument
+                        .split_once(char::is_whitespace)
+                        .map(|(define, value)| (define, value.trim()))
+                        .unwrap_or((argument, ""));
+
+                    if define.is_empty() {
+                        return Err(error(line, "#define without a name".to_owned()));
+                    }
+
+                    self.defines.insert(define.to_owned(), value.to_owned());
+                }
+                "undef" => {
+                    self.defines.remove(argument);
+                }
+                "include" => {
+                    let include = argument
+                        .strip_prefix('"')
+                        .and_then(|argument| argument.strip_suffix('"'))
+                        .ok_or_else(|| {
+                            error(line, format!("expected #include \"path\" but got {}", argument))
+                        })?;
+
+                    let include_path = path.parent().unwrap_or(Path::new("")).join(include);
+
+                    if let Ok(canonical) = fs::canonicalize(&include_path) {
+                        if self.stack.contains(&canonical) {
+                            return Err(error(
+                                line,
+                                format!("include cycle with {}", include_path.display()),
+                            ));
+                        }
+                    }
+
+                    self.process(&include_path)?;
+                }
+                _ => return Err(error(line, format!("unknown directive #{}", name))),
+            }
+        }
+
+        if let Some(condition) = conditions.last() {
+            return Err(error(condition.line, "#ifdef without #endif".to_owned()));
+        }
+
+        self.stack.pop();
+
+        Ok(())
+    }
+
+    fn push_line(&mut self, text: &str, file: usize, line: usize) {
+        let code = &mut self.shader.code;
+        let mut word_start = None;
+
+        for (index, char) in text.char_indices().chain([(text.len(), ' ')]) {
+            let is_word_char = char.is_alphanumeric() || char == '_';
+
+            match (word_start, is_word_char) {
+                (None, true) => word_start = Some(index),
+                (Some(start), false) => {
+                    let word = &text[start..index];
+                    match self.defines.get(word) {
+                        Some(value) if !value.is_empty() => code.push_str(value),
+                        _ => code.push_str(word),
+                    }
+                    word_start = None;
+                }
+                _ => {}
+            }
+
+            if !is_word_char && index < text.len() {
+                code.push(char);
+            }
+        }
+
+        code.push('\n');
+        self.shader.lines.push((file, line));
+    }
+}
+
+/// finds the first `┌─ name:line:column` location in a (colored) shader error message
+fn find_location(message: &str) -> Option<(usize, usize)> {
+    let mut plain = String::with_capacity(message.len());
+    let mut chars = message.chars();
+
+    while let Some(char) = chars.next() {
+        if char == '\x1b' {
+            // skip ANSI color codes like `\x1b[31m`
+            chars.by_ref().find(|char| char.is_ascii_alphabetic());
+        } else {
+            plain.push(char);
+        }
+    }
+
+    plain.split("┌─").skip(1).find_map(|rest| {
+        let location = rest.split_whitespace().next()?;
+        let mut parts = location.rsplitn(3, ':');
+
+        let column = parts.next()?.parse().ok()?;
+        let line = parts.next()?.parse().ok()?;
+
+        Some((line, column))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// empty directory in the temp dir which only this test uses
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "wgpu-noboiler-preprocessor-{}-{}",
+            std::process::id(),
+            name
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_files(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = temp_dir(name);
+        for (file, code) in files {
+            fs::write(dir.join(file), code).unwrap();
+        }
+        dir
+    }
+
+    fn preprocessor_error(err: NoboilerError) -> (PathBuf, usize, String) {
+        match err {
+            NoboilerError::Preprocessor {
+                path,
+                line,
+                message,
+            } => (path, line, message),
+            err => panic!("expected a preprocessor error but got {}", err),
+        }
+    }
+
+    #[test]
+    fn include_cycle() {
+        let dir = write_files(
+            "include_cycle",
+            &[
+                ("a.wgsl", "#include \"b.wgsl\"\n"),
+                ("b.wgsl", "\n#include \"a.wgsl\"\n"),
+            ],
+        );
+
+        let err = ShaderPreprocessor::new()
+            .process_file(dir.join("a.wgsl"))
+            .err()
+            .unwrap();
+        let (path, line, message) = preprocessor_error(err);
+
+        assert_eq!(path, dir.join("b.wgsl"));
+        assert_eq!(line, 2);
+        assert!(message.starts_with("include cycle"), "{}", message);
+    }
+
+    #[test]
+    fn repeated_include_is_skipped() {
+        let dir = write_files(
+            "repeated_include",
+            &[
+                (
+                    "main.wgsl",
+                    "#include \"common.wgsl\"\n#include \"common.wgsl\"\nmain\n",
+                ),
+                ("common.wgsl", "common\n"),
+            ],
+        );
+
+        let shader = ShaderPreprocessor::new()
+            .process_file(dir.join("main.wgsl"))
+            .unwrap();
+
+        assert_eq!(shader.code(), "common\nmain\n");
+        assert_eq!(
+            shader.files(),
+            [dir.join("main.wgsl"), dir.join("common.wgsl")]
+        );
+    }
+
+    #[test]
+    fn nested_conditions() {
+        let dir = write_files(
+            "nested_conditions",
+            &[(
+                "main.wgsl",
+                "#ifdef A\n\
+                 #ifdef B\n\
+                 a_b\n\
+                 #else\n\
+                 a_not_b\n\
+                 #endif\n\
+                 #else\n\
+                 #ifndef B\n\
+                 not_a_not_b\n\
+                 #endif\n\
+                 #endif\n\
+                 end\n",
+            )],
+        );
+        let path = dir.join("main.wgsl");
+
+        let code = |preprocessor: ShaderPreprocessor| {
+            preprocessor.process_file(&path).unwrap().code().to_owned()
+        };
+
+        assert_eq!(code(ShaderPreprocessor::new()), "not_a_not_b\nend\n");
+        assert_eq!(
+            code(ShaderPreprocessor::new().define("A", "")),
+            "a_not_b\nend\n"
+        );
+        assert_eq!(
+            code(ShaderPreprocessor::new().define("A", "").define("B", "")),
+            "a_b\nend\n"
+        );
+        assert_eq!(code(ShaderPreprocessor::new().define("B", "")), "end\n");
+    }
+
+    #[test]
+    fn second_else() {
+        let dir = write_files(
+            "second_else",
+            &[("main.wgsl", "#ifdef A\n#else\n#else\n#endif\n")],
+        );
+
+        let err = ShaderPreprocessor::new()
+            .process_file(dir.join("main.wgsl"))
+            .err()
+            .unwrap();
+        let (_, line, message) = preprocessor_error(err);
+
+        assert_eq!(line, 3);
+        assert_eq!(message, "second #else for the same #ifdef");
+    }
+
+    #[test]
+    fn unterminated_ifdef() {
+        let dir = write_files(
+            "unterminated_ifdef",
+            &[("main.wgsl", "code\n#ifdef A\n#ifdef B\n#endif\n")],
+        );
+
+        let err = ShaderPreprocessor::new()
+            .process_file(dir.join("main.wgsl"))
+            .err()
+            .unwrap();
+        let (_, line, message) = preprocessor_error(err);
+
+        assert_eq!(line, 2);
+        assert_eq!(message, "#ifdef without #endif");
+    }
+
+    #[test]
+    fn define_substitution() {
+        let dir = write_files(
+            "define_substitution",
+            &[(
+                "main.wgsl",
+                "#define COUNT 4u\n\
+                 #define FLAG\n\
+                 let a = COUNT + COUNTER + FLAG;\n\
+                 #undef COUNT\n\
+                 let b = COUNT * SCALE;\n",
+            )],
+        );
+
+        let shader = ShaderPreprocessor::new()
+            .define("SCALE", "2.0")
+            .process_file(dir.join("main.wgsl"))
+            .unwrap();
+
+        assert_eq!(
+            shader.code(),
+            "let a = 4u + COUNTER + FLAG;\nlet b = COUNT * 2.0;\n"
+        );
+    }
+
+    #[test]
+    fn map_line_of_naga_error() {
+        let dir = write_files(
+            "map_line",
+            &[
+                ("main.wgsl", "#include \"helper.wgsl\"\n@vertex\nfn vs_main() -> @builtin(position) vec4<f32> {\n    return vec4<f32>(undefined_thing, 0.0, 0.0, 1.0);\n}\n"),
+                ("helper.wgsl", "fn helper() -> f32 {\n    return 1.0;\n}\n"),
+            ],
+        );
+
+        let shader = ShaderPreprocessor::new()
+            .process_file(dir.join("main.wgsl"))
+            .unwrap();
+
+        // message of wgpu 0.15 for the shader above
+        let message = "Validation Error\n\nCaused by:\n    In Device::create_shader_module\n      note: label = `Render Pipeline Shader`\n    \n\
+            Shader 'Render Pipeline Shader' parsing error: no definition in scope for identifier: 'undefined_thing'\n  \
+            \x1b[34m┌─\x1b[0m wgsl:6:22\n  │\n6 │     return vec4<f32>(undefined_thing, 0.0, 0.0, 1.0);\n  \
+            │                      ^^^^^^^^^^^^^^^ unknown identifier\n\n\n    no definition in scope for identifier: 'undefined_thing'\n";
+
+        assert_eq!(find_location(message), Some((6, 22)));
+
+        let (path, line) = shader.map_line(6).unwrap();
+        assert_eq!(path, dir.join("main.wgsl"));
+        assert_eq!(line, 4);
+
+        let (path, line) = shader.map_line(2).unwrap();
+        assert_eq!(path, dir.join("helper.wgsl"));
+        assert_eq!(line, 2);
+
+        assert!(shader.map_line(0).is_none());
+        assert!(shader.map_line(8).is_none());
+    }
+
+    #[test]
+    fn find_location_without_location() {
+        assert_eq!(find_location("Validation Error"), None);
+    }
+}
@@ -0,0 +1,421 @@
+//! golden-image regression testing
+//!
+//! renders an [AppCreator::headless] app and compares the result with a stored reference PNG.
+//! set the environment variable `WGPU_NOBOILER_UPDATE_GOLDEN=1` to (re)write the reference images
+
+use std::env;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use image::{ImageError, Rgba, RgbaImage};
+
+use crate::app::AppCreator;
+use crate::error::NoboilerError;
+
+/// environment variable which makes [GoldenImage] overwrite the reference images instead of comparing them
+pub const UPDATE_GOLDEN_ENV: &str = "WGPU_NOBOILER_UPDATE_GOLDEN";
+
+/// compares rendered frames with a reference PNG
+pub struct GoldenImage {
+    reference: PathBuf,
+    diff: PathBuf,
+    actual: PathBuf,
+
+    tolerance: u8,
+    max_mismatched_pixels: usize,
+}
+
+impl GoldenImage {
+    /// creates [GoldenImage] which compares against the PNG at the path
+    ///
+    /// on failure the diff image is written next to it as `<name>.diff.png` and the rendered image as `<name>.actual.png`
+    pub fn new(reference: impl AsRef<Path>) -> GoldenImage {
+        let reference = reference.as_ref().to_path_buf();
+
+        GoldenImage {
+            diff: reference.with_extension("diff.png"),
+            actual: reference.with_extension("actual.png"),
+            reference,
+
+            tolerance: 0,
+            max_mismatched_pixels: 0,
+        }
+    }
+
+    /// sets the max difference per color channel for two pixels to still count as equal
+    ///
+    /// default: 0
+    pub fn tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// sets how many pixels are allowed to differ before the comparison fails
+    ///
+    /// default: 0
+    pub fn max_mismatched_pixels(mut self, max_mismatched_pixels: usize) -> Self {
+        self.max_mismatched_pixels = max_mismatched_pixels;
+        self
+    }
+
+    /// sets the path the diff image gets written to on failure
+    pub fn diff_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.diff = path.as_ref().to_path_buf();
+        self
+    }
+
+    /// renders the [headless](AppCreator::headless) app and compares the last frame with the reference
+    ///
+    /// # Panics
+    ///
+    /// if the [AppCreator] was not created with [AppCreator::headless]
+    pub fn check<T>(&self, app: AppCreator<T>) -> Result<(), GoldenImageError> {
+        let (app_data, _) = app
+            .try_run_headless()
+            .map_err(|err| GoldenImageError::Setup(err.error))?;
+
+        let image = app_data
+            .capture_frame()
+            .expect("headless app has an offscreen texture");
+
+        self.check_image(&image)
+    }
+
+    /// same as [GoldenImage::check] but panics with a readable message on failure
+    pub fn assert<T>(&self, app: AppCreator<T>) {
+        if let Err(err) = self.check(app) {
+            panic!("{}", err);
+        }
+    }
+
+    /// compares the image with the reference
+    pub fn check_image(&self, image: &RgbaImage) -> Result<(), GoldenImageError> {
+        if env::var_os(UPDATE_GOLDEN_ENV).is_some_and(|value| value != "0") {
+            image.save(&self.reference)?;
+            return Ok(());
+        }
+
+        if !self.reference.exists() {
+            return Err(GoldenImageError::MissingReference(self.reference.clone()));
+        }
+
+        let reference = image::open(&self.reference)?.into_rgba8();
+
+        if reference.dimensions() != image.dimensions() {
+            image.save(&self.actual)?;
+
+            return Err(GoldenImageError::SizeMismatch {
+                expected: reference.dimensions(),
+                actual: image.dimensions(),
+            });
+        }
+
+        let comparison = compare_images(&reference, image, self.tolerance);
+
+        if comparison.mismatched_pixels > self.max_mismatched_pixels {
+            comparison.diff.save(&self.diff)?;
+            image.save(&self.actual)?;
+
+            return Err(GoldenImageError::Mismatch {
+                mismatched_pixels: comparison.mismatched_pixels,
+                max_difference: comparison.max_difference,
+                diff: self.diff.clone(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// result of [compare_images]
+pub struct ImageComparison {
+    /// count of pixels where at least one channel differs by more than the tolerance
+    pub mismatched_pixels: usize,
+    /// biggest difference of a single channel
+    pub max_difference: u8,
+    /// mismatched pixels are red, all others are the dimmed expected image
+    pub diff: RgbaImage,
+}
+
+/// compares two images of the same size pixel by pixel
+///
+/// # Panics
+///
+/// if the images have different dimensions
+pub fn compare_images(expected: &RgbaImage, actual: &RgbaImage, tolerance: u8) -> ImageComparison {
+    assert_eq!(
+        expected.dimensions(),
+        actual.dimensions(),
+        "compared images need the same dimensions"
+    );
+
+    let mut mismatched_pixels = 0;
+    let mut max_difference = 0;
+
+    let mut diff = RgbaImage::new(expected.width(), expected.height());
+
+    for ((expected, actual), diff) in expected.pixels().zip(actual.pixels()).zip(diff.pixels_mut()) {
+        let difference = expected
+            .0
+            .iter()
+            .zip(actual.0.iter())
+            .map(|(expected, actual)| expected.abs_diff(*actual))
+            .max()
+            .unwrap_or(0);
+
+        max_difference = max_difference.max(difference);
+
+        *diff = if difference > tolerance {
+            mismatched_pixels += 1;
+            Rgba([255, 0, 0, 255])
+        } else {
+            let [r, g, b, _] = expected.0;
+            let luma = ((r as u32 + g as u32 + b as u32) / 3 / 4) as u8;
+            Rgba([luma, luma, luma, 255])
+        };
+    }
+
+    ImageComparison {
+        mismatched_pixels,
+        max_difference,
+        diff,
+    }
+}
+
+/// reasons why a [GoldenImage] comparison failed
+#[derive(Debug)]
+pub enum GoldenImageError {
+    /// the reference image does not exist yet
+    MissingReference(PathBuf),
+    /// the rendered image has a different size than the reference
+    SizeMismatch {
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+    /// too many pixels differ from the reference
+    Mismatch {
+        mismatched_pixels: usize,
+        max_difference: u8,
+        diff: PathBuf,
+    },
+    /// the headless app could not be set up
+    Setup(NoboilerError),
+    /// an image could not be read or written
+    Image(ImageError),
+}
+
+impl Display for GoldenImageError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GoldenImageError::MissingReference(path) => write!(
+                f,
+                "reference image {} does not exist, run with {}=1 to create it",
+                path.display(),
+                UPDATE_GOLDEN_ENV
+            ),
+            GoldenImageError::SizeMismatch { expected, actual } => write!(
+                f,
+                "rendered image is {}x{} but the reference is {}x{}",
+                actual.0, actual.1, expected.0, expected.1
+            ),
+            GoldenImageError::Mismatch {
+                mismatched_pixels,
+                max_difference,
+                diff,
+            } => write!(
+                f,
+                "{} pixels differ from the reference (max channel difference {}), diff written to {}",
+                mismatched_pixels,
+                max_difference,
+                diff.display()
+            ),
+            GoldenImageError::Setup(err) => write!(f, "{}", err),
+            GoldenImageError::Image(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for GoldenImageError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GoldenImageError::Setup(err) => Some(err),
+            GoldenImageError::Image(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ImageError> for GoldenImageError {
+    fn from(err: ImageError) -> Self {
+        GoldenImageError::Image(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::iter::once;
+
+    use wgpu::Color;
+
+    use super::*;
+    use crate::render_pass::RenderPassCreator;
+
+    /// empty directory in the temp dir which only this test uses
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!(
+            "wgpu-noboiler-testing-{}-{}",
+            std::process::id(),
+            name
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn filled(width: u32, height: u32, color: [u8; 4]) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, Rgba(color))
+    }
+
+    #[test]
+    fn identical_images() {
+        let image = filled(4, 4, [10, 20, 30, 255]);
+
+        let comparison = compare_images(&image, &image, 0);
+
+        assert_eq!(comparison.mismatched_pixels, 0);
+        assert_eq!(comparison.max_difference, 0);
+        assert_eq!(comparison.diff.dimensions(), (4, 4));
+    }
+
+    #[test]
+    fn difference_within_and_over_tolerance() {
+        let expected = filled(4, 4, [100, 100, 100, 255]);
+        let mut actual = expected.clone();
+        actual.put_pixel(1, 1, Rgba([103, 100, 100, 255]));
+        actual.put_pixel(2, 2, Rgba([100, 90, 100, 255]));
+
+        let comparison = compare_images(&expected, &actual, 3);
+        assert_eq!(comparison.mismatched_pixels, 1);
+        assert_eq!(comparison.max_difference, 10);
+        assert_eq!(comparison.diff.get_pixel(2, 2), &Rgba([255, 0, 0, 255]));
+        assert_ne!(comparison.diff.get_pixel(1, 1), &Rgba([255, 0, 0, 255]));
+
+        let comparison = compare_images(&expected, &actual, 10);
+        assert_eq!(comparison.mismatched_pixels, 0);
+    }
+
+    #[test]
+    fn max_mismatched_pixels() {
+        let dir = temp_dir("max_mismatched_pixels");
+        let reference = dir.join("reference.png");
+
+        let expected = filled(4, 4, [0, 0, 0, 255]);
+        expected.save(&reference).unwrap();
+
+        let mut actual = expected.clone();
+        actual.put_pixel(0, 0, Rgba([255, 255, 255, 255]));
+        actual.put_pixel(3, 3, Rgba([255, 255, 255, 255]));
+
+        let golden_image = GoldenImage::new(&reference);
+        assert!(golden_image
+            .max_mismatched_pixels(2)
+            .check_image(&actual)
+            .is_ok());
+
+        let golden_image = GoldenImage::new(&reference).max_mismatched_pixels(1);
+        assert!(matches!(
+            golden_image.check_image(&actual),
+            Err(GoldenImageError::Mismatch {
+                mismatched_pixels: 2,
+                max_difference: 255,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn size_mismatch() {
+        let dir = temp_dir("size_mismatch");
+        let reference = dir.join("reference.png");
+        filled(4, 4, [0, 0, 0, 255]).save(&reference).unwrap();
+
+        let result = GoldenImage::new(&reference).check_image(&filled(2, 3, [0, 0, 0, 255]));
+
+        assert!(matches!(
+            result,
+            Err(GoldenImageError::SizeMismatch {
+                expected: (4, 4),
+                actual: (2, 3),
+            })
+        ));
+        assert!(dir.join("reference.actual.png").exists());
+    }
+
+    #[test]
+    fn missing_reference() {
+        let dir = temp_dir("missing_reference");
+        let reference = dir.join("reference.png");
+
+        let result = GoldenImage::new(&reference).check_image(&filled(4, 4, [0, 0, 0, 255]));
+
+        assert!(
+            matches!(result, Err(GoldenImageError::MissingReference(path)) if path == reference)
+        );
+        assert!(!reference.exists());
+    }
+
+    #[test]
+    fn writes_diff_and_actual() {
+        let dir = temp_dir("writes_diff_and_actual");
+        let reference = dir.join("reference.png");
+        filled(4, 4, [0, 0, 0, 255]).save(&reference).unwrap();
+
+        let actual = filled(4, 4, [0, 0, 50, 255]);
+        let result = GoldenImage::new(&reference)
+            .diff_path(dir.join("custom.diff.png"))
+            .check_image(&actual);
+
+        match result {
+            Err(GoldenImageError::Mismatch {
+                mismatched_pixels,
+                diff,
+                ..
+            }) => {
+                assert_eq!(mismatched_pixels, 16);
+                assert_eq!(diff, dir.join("custom.diff.png"));
+            }
+            _ => panic!("expected a mismatch"),
+        }
+
+        let diff = image::open(dir.join("custom.diff.png"))
+            .unwrap()
+            .into_rgba8();
+        assert_eq!(diff.get_pixel(0, 0), &Rgba([255, 0, 0, 255]));
+
+        let written = image::open(dir.join("reference.actual.png"))
+            .unwrap()
+            .into_rgba8();
+        assert_eq!(written, actual);
+    }
+
+    #[test]
+    fn check_clear_color_frame() {
+        let dir = temp_dir("check_clear_color_frame");
+        let reference = dir.join("reference.png");
+        filled(8, 8, [255, 0, 0, 255]).save(&reference).unwrap();
+
+        let app = AppCreator::headless((), 8, 8).render(|app_data, _, mut encoder, view| {
+            {
+                RenderPassCreator::new(&view)
+                    .clear_color(Color::RED)
+                    .build(&mut encoder);
+            }
+
+            app_data.queue.submit(once(encoder.finish()));
+        });
+
+        GoldenImage::new(&reference).tolerance(1).assert(app);
+    }
+}
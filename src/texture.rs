@@ -0,0 +1,258 @@
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+
+use image::RgbaImage;
+use wgpu::{
+    AddressMode, BindGroup, Device, ErrorFilter, Extent3d, FilterMode, ImageDataLayout, Queue,
+    Sampler, SamplerDescriptor, ShaderStages, Texture, TextureDescriptor, TextureDimension,
+    TextureFormat, TextureUsages, TextureView, TextureViewDescriptor,
+};
+
+use crate::bind_group::{BindGroupCreator, BindGroupLayoutCreator, SimpleBindGroupLayout};
+use crate::error::NoboilerError;
+
+/// Builder Patter for wgpu [Texture] loaded from an image (PNG | JPEG)
+pub struct TextureCreator<'a> {
+    device: &'a Device,
+    queue: &'a Queue,
+
+    source: TextureSource<'a>,
+
+    srgb: bool,
+    filter: FilterMode,
+    address_mode: AddressMode,
+
+    label: &'a str,
+}
+
+enum TextureSource<'a> {
+    Path(&'a Path),
+    Bytes(&'a [u8]),
+    Image(&'a RgbaImage),
+}
+
+impl<'a> TextureCreator<'a> {
+    /// creates an [TextureCreator] where the image is read from the path
+    pub fn from_path(
+        path: &'a (impl AsRef<Path> + ?Sized),
+        device: &'a Device,
+        queue: &'a Queue,
+    ) -> TextureCreator<'a> {
+        Self::new(TextureSource::Path(path.as_ref()), device, queue)
+    }
+
+    /// creates an [TextureCreator] where the image is decoded from the bytes (e.g. from [include_bytes])
+    pub fn from_bytes(bytes: &'a [u8], device: &'a Device, queue: &'a Queue) -> TextureCreator<'a> {
+        Self::new(TextureSource::Bytes(bytes), device, queue)
+    }
+
+    /// creates an [TextureCreator] from an already decoded [RgbaImage]
+    pub fn from_image(
+        image: &'a RgbaImage,
+        device: &'a Device,
+        queue: &'a Queue,
+    ) -> TextureCreator<'a> {
+        Self::new(TextureSource::Image(image), device, queue)
+    }
+
+    fn new(source: TextureSource<'a>, device: &'a Device, queue: &'a Queue) -> TextureCreator<'a> {
+        TextureCreator {
+            device,
+            queue,
+            source,
+            srgb: true,
+            filter: FilterMode::Linear,
+            address_mode: AddressMode::ClampToEdge,
+            label: "Texture",
+        }
+    }
+
+    /// sets if the image contains colors (sRGB) or linear data (e.g. normal maps)
+    ///
+    /// default: true
+    pub fn srgb(mut self, srgb: bool) -> Self {
+        self.srgb = srgb;
+        self
+    }
+
+    /// sets the [FilterMode] used when magnifying and minifying
+    ///
+    /// default: Linear
+    pub fn filter(mut self, filter: FilterMode) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// sets the [AddressMode] used for all directions
+    ///
+    /// default: ClampToEdge
+    pub fn address_mode(mut self, address_mode: AddressMode) -> Self {
+        self.address_mode = address_mode;
+        self
+    }
+
+    /// sets label (name)
+    pub fn label(mut self, label: &'a str) -> Self {
+        self.label = label;
+        self
+    }
+
+    /// creates a [SimpleTexture]
+    ///
+    /// # Panics
+    ///
+    /// if the image could not be read or decoded or the texture could not be created (e.g. it is too big), use [TextureCreator::try_build] to handle the error
+    pub fn build(&self) -> SimpleTexture {
+        self.try_build().unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// same as [TextureCreator::build] but returns an [NoboilerError] if the image could not be read or decoded or the texture could not be created
+    pub fn try_build(&self) -> Result<SimpleTexture, NoboilerError> {
+        let decoded;
+
+        let image = match self.source {
+            TextureSource::Path(path) => {
+                decoded = image::open(path)
+                    .map_err(|error| NoboilerError::TextureFile {
+                        path: PathBuf::from(path),
+                        error,
+                    })?
+                    .to_rgba8();
+                &decoded
+            }
+            TextureSource::Bytes(bytes) => {
+                decoded = image::load_from_memory(bytes)
+                    .map_err(NoboilerError::Texture)?
+                    .to_rgba8();
+                &decoded
+            }
+            TextureSource::Image(image) => image,
+        };
+
+        self.create(image)
+    }
+
+    fn create(&self, image: &RgbaImage) -> Result<SimpleTexture, NoboilerError> {
+        let size = Extent3d {
+            width: image.width(),
+            height: image.height(),
+            depth_or_array_layers: 1,
+        };
+
+        let format = if self.srgb {
+            TextureFormat::Rgba8UnormSrgb
+        } else {
+            TextureFormat::Rgba8Unorm
+        };
+
+        self.device.push_error_scope(ErrorFilter::Validation);
+
+        let texture = self.device.create_texture(&TextureDescriptor {
+            label: Some(self.label),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+
+        self.queue.write_texture(
+            texture.as_image_copy(),
+            image,
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: NonZeroU32::new(4 * size.width),
+                rows_per_image: NonZeroU32::new(size.height),
+            },
+            size,
+        );
+
+        if let Some(err) = pollster::block_on(self.device.pop_error_scope()) {
+            return Err(NoboilerError::TextureCreation(err));
+        }
+
+        let view = texture.create_view(&TextureViewDescriptor::default());
+
+        let sampler = self.device.create_sampler(&SamplerDescriptor {
+            label: Some(&(self.label.to_owned() + " Sampler")),
+            address_mode_u: self.address_mode,
+            address_mode_v: self.address_mode,
+            address_mode_w: self.address_mode,
+            mag_filter: self.filter,
+            min_filter: self.filter,
+            mipmap_filter: FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        Ok(SimpleTexture {
+            texture,
+            view,
+            sampler,
+        })
+    }
+}
+
+/// wrapper for [Texture] together with its [TextureView] and [Sampler]
+pub struct SimpleTexture {
+    texture: Texture,
+    view: TextureView,
+    sampler: Sampler,
+}
+
+impl SimpleTexture {
+    /// creates a [SimpleBindGroupLayout] with a texture (binding 0) and a sampler (binding 1) as used by [SimpleTexture::bind_group]
+    ///
+    /// use its [layout](SimpleBindGroupLayout::layout) with [RenderPipelineCreator::add_bind_group](crate::render_pipeline::RenderPipelineCreator::add_bind_group)
+    pub fn bind_group_layout(device: &Device, visibility: ShaderStages) -> SimpleBindGroupLayout {
+        BindGroupLayoutCreator::new(device)
+            .label("Texture Bind Group Layout")
+            .add_texture(visibility)
+            .add_sampler(visibility)
+            .build()
+    }
+
+    /// creates a [BindGroup] with the view (binding 0) and the sampler (binding 1)
+    ///
+    /// # Panics
+    ///
+    /// if the layout does not match, use [SimpleTexture::try_bind_group] to handle the error
+    pub fn bind_group(&self, device: &Device, layout: &SimpleBindGroupLayout) -> BindGroup {
+        self.try_bind_group(device, layout)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// same as [SimpleTexture::bind_group] but returns an [NoboilerError] if the layout does not match
+    pub fn try_bind_group(
+        &self,
+        device: &Device,
+        layout: &SimpleBindGroupLayout,
+    ) -> Result<BindGroup, NoboilerError> {
+        BindGroupCreator::new(device, layout)
+            .label("Texture Bind Group")
+            .add_texture_view(&self.view)
+            .add_sampler(&self.sampler)
+            .try_build()
+    }
+
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    pub fn view(&self) -> &TextureView {
+        &self.view
+    }
+
+    pub fn sampler(&self) -> &Sampler {
+        &self.sampler
+    }
+
+    /// (width, height) in pixels
+    pub fn size(&self) -> (u32, u32) {
+        let size = self.texture.size();
+        (size.width, size.height)
+    }
+}
@@ -0,0 +1,212 @@
+use std::mem;
+use std::num::NonZeroU64;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use bytemuck::Pod;
+use wgpu::{
+    BindGroupEntry, BindingResource, Buffer, BufferAddress, BufferBinding, BufferDescriptor,
+    BufferSlice, BufferUsages, Device, Maintain, Queue, COPY_BUFFER_ALIGNMENT,
+};
+
+use crate::buffer::align;
+
+/// size of one chunk, bigger uploads get their own chunk
+const CHUNK_SIZE: BufferAddress = 1 << 20;
+
+/// part of a [Buffer] which got written by [AppData::upload](crate::app::AppData::upload)
+///
+/// only valid until the end of the frame it was uploaded in, afterwards the memory gets reused
+pub struct TransientSlice {
+    buffer: Arc<Buffer>,
+    offset: BufferAddress,
+    size: BufferAddress,
+}
+
+impl TransientSlice {
+    /// [BufferSlice] of the uploaded data, use it with [RenderPass::set_vertex_buffer](wgpu::RenderPass::set_vertex_buffer) | [RenderPass::set_index_buffer](wgpu::RenderPass::set_index_buffer)
+    pub fn slice(&self) -> BufferSlice<'_> {
+        self.buffer.slice(self.offset..self.offset + self.size)
+    }
+
+    /// the shared [Buffer] the data got written into
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    /// offset in bytes inside of [TransientSlice::buffer]
+    pub fn offset(&self) -> BufferAddress {
+        self.offset
+    }
+
+    /// size of the uploaded data in bytes
+    pub fn size(&self) -> BufferAddress {
+        self.size
+    }
+
+    /// [BindingResource] of the uploaded data (e.g. as uniform)
+    pub fn binding_resource(&self) -> BindingResource<'_> {
+        BindingResource::Buffer(BufferBinding {
+            buffer: &self.buffer,
+            offset: self.offset,
+            size: NonZeroU64::new(self.size),
+        })
+    }
+
+    /// [BindGroupEntry] of the uploaded data at the binding
+    pub fn bind_group_entry(&self, binding: u32) -> BindGroupEntry<'_> {
+        BindGroupEntry {
+            binding,
+            resource: self.binding_resource(),
+        }
+    }
+}
+
+/// memory usage of the [uploads](crate::app::AppData::upload) in bytes (including alignment padding)
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct UploadStats {
+    /// uploaded in the current frame so far
+    pub current_frame: BufferAddress,
+    /// uploaded in the last finished frame
+    pub last_frame: BufferAddress,
+    /// the most which got uploaded in one frame
+    pub high_water_mark: BufferAddress,
+    /// size of all chunks (in use or free)
+    pub allocated: BufferAddress,
+    /// count of all chunks
+    pub chunks: usize,
+}
+
+struct Chunk {
+    buffer: Arc<Buffer>,
+    offset: BufferAddress,
+}
+
+impl Chunk {
+    fn size(&self) -> BufferAddress {
+        self.buffer.size()
+    }
+}
+
+/// hands out [TransientSlices](TransientSlice) of bigger chunks
+///
+/// chunks used in a frame get recycled once the gpu finished the work submitted in that frame
+pub(crate) struct UploadAllocator {
+    alignment: BufferAddress,
+
+    /// chunks of the current frame, the last one gets filled
+    active: Vec<Chunk>,
+    /// chunks of finished frames which may still be used by the gpu
+    in_flight: Vec<(Chunk, Arc<AtomicBool>)>,
+    free: Vec<Chunk>,
+
+    stats: UploadStats,
+}
+
+impl UploadAllocator {
+    pub(crate) fn new(device: &Device) -> UploadAllocator {
+        UploadAllocator {
+            alignment: (device.limits().min_uniform_buffer_offset_alignment as BufferAddress)
+                .max(COPY_BUFFER_ALIGNMENT),
+            active: vec![],
+            in_flight: vec![],
+            free: vec![],
+            stats: UploadStats::default(),
+        }
+    }
+
+    pub(crate) fn stats(&self) -> UploadStats {
+        self.stats
+    }
+
+    pub(crate) fn upload<T: Pod>(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        data: &[T],
+    ) -> TransientSlice {
+        assert!(!data.is_empty(), "can not upload an empty slice");
+
+        let bytes: &[u8] = bytemuck::cast_slice(data);
+        let size = mem::size_of_val(data) as BufferAddress;
+        let reserved = align(size, COPY_BUFFER_ALIGNMENT);
+
+        let fits = |chunk: &Chunk| align(chunk.offset, self.alignment) + reserved <= chunk.size();
+
+        if !self.active.last().is_some_and(fits) {
+            let chunk = match self.free.iter().position(|chunk| chunk.size() >= reserved) {
+                Some(index) => self.free.swap_remove(index),
+                None => self.create_chunk(device, reserved),
+            };
+            self.active.push(chunk);
+        }
+
+        let chunk = self.active.last_mut().unwrap();
+        let offset = align(chunk.offset, self.alignment);
+
+        if reserved == size {
+            queue.write_buffer(&chunk.buffer, offset, bytes);
+        } else {
+            let mut padded = bytes.to_vec();
+            padded.resize(reserved as usize, 0);
+            queue.write_buffer(&chunk.buffer, offset, &padded);
+        }
+
+        self.stats.current_frame += offset - chunk.offset + reserved;
+        chunk.offset = offset + reserved;
+
+        TransientSlice {
+            buffer: chunk.buffer.clone(),
+            offset,
+            size,
+        }
+    }
+
+    /// has to be called after the work of the frame got submitted
+    pub(crate) fn end_frame(&mut self, device: &Device, queue: &Queue) {
+        for chunk in self.active.drain(..) {
+            let done = Arc::new(AtomicBool::new(false));
+
+            let callback_done = done.clone();
+            queue.on_submitted_work_done(move || callback_done.store(true, Ordering::Release));
+
+            self.in_flight.push((chunk, done));
+        }
+
+        device.poll(Maintain::Poll);
+
+        let (done, in_flight): (Vec<_>, Vec<_>) = mem::take(&mut self.in_flight)
+            .into_iter()
+            .partition(|(_, done)| done.load(Ordering::Acquire));
+        self.in_flight = in_flight;
+
+        self.free.extend(done.into_iter().map(|(chunk, _)| Chunk {
+            offset: 0,
+            ..chunk
+        }));
+
+        self.stats.last_frame = self.stats.current_frame;
+        self.stats.high_water_mark = self.stats.high_water_mark.max(self.stats.current_frame);
+        self.stats.current_frame = 0;
+    }
+
+    fn create_chunk(&mut self, device: &Device, size: BufferAddress) -> Chunk {
+        let size = align(size.max(CHUNK_SIZE), self.alignment);
+
+        self.stats.allocated += size;
+        self.stats.chunks += 1;
+
+        Chunk {
+            buffer: Arc::new(device.create_buffer(&BufferDescriptor {
+                label: Some("Upload Chunk"),
+                size,
+                usage: BufferUsages::VERTEX
+                    | BufferUsages::INDEX
+                    | BufferUsages::UNIFORM
+                    | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })),
+            offset: 0,
+        }
+    }
+}
@@ -2,6 +2,10 @@ use std::mem;
 
 use wgpu::{BufferAddress, VertexAttribute, VertexBufferLayout, VertexStepMode};
 
+/// `#[derive(Vertex)]`, see [wgpu_noboiler_derive::Vertex]
+#[cfg(feature = "derive")]
+pub use wgpu_noboiler_derive::Vertex;
+
 pub trait Vertex<const SIZE: usize> {
     fn descriptor<'a>() -> VertexBufferLayout<'a>
     where
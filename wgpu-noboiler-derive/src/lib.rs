@@ -0,0 +1,238 @@
+//! derive macros for [wgpu-noboiler](https://github.com/ToBinio/wgpu-noboiler), use them with the `derive` feature of it
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Error, Expr, ExprLit, Fields, Ident, Lit,
+    LitInt, Member, Type,
+};
+
+/// implements `Vertex<N>` with one attribute per field
+///
+/// the shader locations count up from 0 in the order of the fields
+///
+/// supported field types:
+/// - `f32` | `u32` | `i32` and arrays of them with up to 4 elements
+/// - `[u8; 2]` | `[u8; 4]` as normalized (`Unorm8x*`), `[i8; 2]` | `[i8; 4]` as `Snorm8x*`
+/// - `[u16; 2]` | `[u16; 4]` | `[i16; 2]` | `[i16; 4]`
+/// - glam vectors (`Vec2`, `Vec3`, `Vec4`, `IVec*`, `UVec*`), matched by name so their size gets checked at compile time
+///
+/// `f64` fields are not mapped because they need [VERTEX_ATTRIBUTE_64BIT](https://docs.rs/wgpu/latest/wgpu/struct.Features.html#associatedconstant.VERTEX_ATTRIBUTE_64BIT),
+/// request that feature and set the format (e.g. `#[vertex(format = Float64x2)]`) to use them
+///
+/// attributes:
+/// - `#[vertex(instance)]` on the struct: steps per instance
+/// - `#[vertex(location = N)]` on a field: uses location N, the following fields continue with N + 1
+/// - `#[vertex(format = Uint8x4)]` on a field: uses the [VertexFormat](https://docs.rs/wgpu/latest/wgpu/enum.VertexFormat.html) instead of the mapped one
+///
+/// the struct has to be `#[repr(C)]` and [Pod](https://docs.rs/bytemuck/latest/bytemuck/trait.Pod.html) to be used in a buffer
+#[proc_macro_derive(Vertex, attributes(vertex))]
+pub fn derive_vertex(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand_vertex(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_vertex(input: DeriveInput) -> Result<TokenStream2, Error> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(
+            input.ident.span(),
+            "Vertex can only be derived for structs",
+        ));
+    };
+
+    let mut instance = false;
+    for attribute in vertex_attributes(&input.attrs) {
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("instance") {
+                instance = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `instance`"))
+            }
+        })?;
+    }
+
+    let members: Vec<(Member, &Type, &[Attribute])> = match &data.fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|field| {
+                let ident = field.ident.clone().unwrap();
+                (Member::Named(ident), &field.ty, &field.attrs[..])
+            })
+            .collect(),
+        Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(index, field)| (Member::from(index), &field.ty, &field.attrs[..]))
+            .collect(),
+        Fields::Unit => vec![],
+    };
+
+    let mut used_locations: Vec<u32> = vec![];
+    let mut next_location = 0;
+    let mut attributes = vec![];
+    let mut size_checks = vec![];
+
+    for (member, ty, attrs) in members {
+        let mut location = None;
+        let mut format = None;
+
+        for attribute in vertex_attributes(attrs) {
+            attribute.parse_nested_meta(|meta| {
+                if meta.path.is_ident("location") {
+                    location = Some(meta.value()?.parse::<LitInt>()?.base10_parse::<u32>()?);
+                    Ok(())
+                } else if meta.path.is_ident("format") {
+                    format = Some(meta.value()?.parse::<Ident>()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `location` or `format`"))
+                }
+            })?;
+        }
+
+        let location = location.unwrap_or(next_location);
+        if used_locations.contains(&location) {
+            return Err(Error::new(
+                member.span(),
+                format!("shader location {} is used more than once", location),
+            ));
+        }
+        used_locations.push(location);
+        next_location = location + 1;
+
+        let format = match format {
+            Some(format) => format,
+            None => {
+                let (format, size) = vertex_format(ty)?;
+
+                // glam types are only matched by name, so another type with the same name fails here
+                let message = format!(
+                    "the size of the field does not match VertexFormat::{}, use #[vertex(format = ...)] to set the VertexFormat",
+                    format
+                );
+                size_checks.push(quote_spanned! {ty.span()=>
+                    const _: () = ::core::assert!(::core::mem::size_of::<#ty>() == #size, #message);
+                });
+
+                Ident::new(format, Span::call_site())
+            }
+        };
+
+        attributes.push(quote! {
+            ::wgpu_noboiler::__private::wgpu::VertexAttribute {
+                format: ::wgpu_noboiler::__private::wgpu::VertexFormat::#format,
+                offset: ::core::mem::offset_of!(Self, #member) as ::wgpu_noboiler::__private::wgpu::BufferAddress,
+                shader_location: #location,
+            }
+        });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let size = attributes.len();
+
+    let step_mode = instance.then(|| {
+        quote! {
+            const STEP_MODE: ::wgpu_noboiler::__private::wgpu::VertexStepMode =
+                ::wgpu_noboiler::__private::wgpu::VertexStepMode::Instance;
+        }
+    });
+
+    Ok(quote! {
+        #(#size_checks)*
+
+        impl #impl_generics ::wgpu_noboiler::vertex::Vertex<#size> for #name #ty_generics #where_clause {
+            #step_mode
+            const ATTRIBS: [::wgpu_noboiler::__private::wgpu::VertexAttribute; #size] = [#(#attributes),*];
+        }
+    })
+}
+
+fn vertex_attributes(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs
+        .iter()
+        .filter(|attribute| attribute.path().is_ident("vertex"))
+}
+
+/// name and size in bytes of the `VertexFormat` variant for the type
+fn vertex_format(ty: &Type) -> Result<(&'static str, usize), Error> {
+    let format = match ty {
+        Type::Path(path) => {
+            let name = path
+                .path
+                .segments
+                .last()
+                .map(|segment| segment.ident.to_string())
+                .unwrap_or_default();
+
+            match name.as_str() {
+                "f32" => Some(("Float32", 4)),
+                "u32" => Some(("Uint32", 4)),
+                "i32" => Some(("Sint32", 4)),
+                "Vec2" => Some(("Float32x2", 8)),
+                "Vec3" => Some(("Float32x3", 12)),
+                "Vec4" => Some(("Float32x4", 16)),
+                "IVec2" => Some(("Sint32x2", 8)),
+                "IVec3" => Some(("Sint32x3", 12)),
+                "IVec4" => Some(("Sint32x4", 16)),
+                "UVec2" => Some(("Uint32x2", 8)),
+                "UVec3" => Some(("Uint32x3", 12)),
+                "UVec4" => Some(("Uint32x4", 16)),
+                _ => None,
+            }
+        }
+        Type::Array(array) => {
+            let element = match &*array.elem {
+                Type::Path(path) => path.path.get_ident().map(Ident::to_string),
+                _ => None,
+            };
+
+            let len = match &array.len {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Int(len), ..
+                }) => len.base10_parse::<usize>().ok(),
+                _ => None,
+            };
+
+            match (element.as_deref(), len) {
+                (Some("f32"), Some(1)) => Some(("Float32", 4)),
+                (Some("f32"), Some(2)) => Some(("Float32x2", 8)),
+                (Some("f32"), Some(3)) => Some(("Float32x3", 12)),
+                (Some("f32"), Some(4)) => Some(("Float32x4", 16)),
+                (Some("u32"), Some(1)) => Some(("Uint32", 4)),
+                (Some("u32"), Some(2)) => Some(("Uint32x2", 8)),
+                (Some("u32"), Some(3)) => Some(("Uint32x3", 12)),
+                (Some("u32"), Some(4)) => Some(("Uint32x4", 16)),
+                (Some("i32"), Some(1)) => Some(("Sint32", 4)),
+                (Some("i32"), Some(2)) => Some(("Sint32x2", 8)),
+                (Some("i32"), Some(3)) => Some(("Sint32x3", 12)),
+                (Some("i32"), Some(4)) => Some(("Sint32x4", 16)),
+                (Some("u8"), Some(2)) => Some(("Unorm8x2", 2)),
+                (Some("u8"), Some(4)) => Some(("Unorm8x4", 4)),
+                (Some("i8"), Some(2)) => Some(("Snorm8x2", 2)),
+                (Some("i8"), Some(4)) => Some(("Snorm8x4", 4)),
+                (Some("u16"), Some(2)) => Some(("Uint16x2", 4)),
+                (Some("u16"), Some(4)) => Some(("Uint16x4", 8)),
+                (Some("i16"), Some(2)) => Some(("Sint16x2", 4)),
+                (Some("i16"), Some(4)) => Some(("Sint16x4", 8)),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+
+    format.ok_or_else(|| {
+        Error::new(
+            ty.span(),
+            "unsupported vertex field type, use #[vertex(format = ...)] to set the VertexFormat",
+        )
+    })
+}
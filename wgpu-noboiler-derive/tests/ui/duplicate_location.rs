@@ -0,0 +1,12 @@
+use wgpu_noboiler_derive::Vertex;
+
+#[repr(C)]
+#[derive(Vertex)]
+struct Vertex {
+    #[vertex(location = 1)]
+    position: [f32; 3],
+    #[vertex(location = 1)]
+    color: [f32; 3],
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use wgpu_noboiler_derive::Vertex;
+
+#[repr(C)]
+struct Vec3 {
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+#[repr(C)]
+#[derive(Vertex)]
+struct Vertex {
+    position: Vec3,
+}
+
+fn main() {}
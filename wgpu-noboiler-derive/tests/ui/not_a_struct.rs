@@ -0,0 +1,8 @@
+use wgpu_noboiler_derive::Vertex;
+
+#[derive(Vertex)]
+enum Vertex {
+    Position([f32; 3]),
+}
+
+fn main() {}
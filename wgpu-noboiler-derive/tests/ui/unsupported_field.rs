@@ -0,0 +1,10 @@
+use wgpu_noboiler_derive::Vertex;
+
+#[repr(C)]
+#[derive(Vertex)]
+struct Vertex {
+    position: [f32; 3],
+    weight: f64,
+}
+
+fn main() {}